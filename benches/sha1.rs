@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const SIZES: [usize; 4] = [64, 1 << 10, 64 << 10, 16 << 20];
+
+fn bench_hash(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash");
+
+    for &size in SIZES.iter() {
+        let input = vec![0x5a; size];
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &input, |b, input| {
+            b.iter(|| bad_sha1::hash(black_box(input)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_compress(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compress");
+    let block = [0x5a; 64];
+    let mut state = bad_sha1::consts::HASH_CONSTANTS;
+
+    group.throughput(Throughput::Bytes(64));
+    group.bench_function("block", |b| {
+        b.iter(|| bad_sha1::compress(black_box(&mut state), black_box(&block)))
+    });
+
+    group.finish();
+}
+
+fn bench_hash4(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash4");
+
+    for &size in SIZES[..3].iter() {
+        let inputs: Vec<Vec<u8>> = (0..4).map(|i| vec![i as u8; size]).collect();
+        let lanes = [&inputs[0][..], &inputs[1], &inputs[2], &inputs[3]];
+
+        group.throughput(Throughput::Bytes(4 * size as u64));
+        group.bench_with_input(BenchmarkId::new("interleaved", size), &lanes, |b, lanes| {
+            b.iter(|| bad_sha1::hash4(black_box(*lanes)))
+        });
+        group.bench_with_input(BenchmarkId::new("sequential", size), &lanes, |b, lanes| {
+            b.iter(|| black_box(*lanes).map(bad_sha1::hash))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_hash, bench_compress, bench_hash4);
+criterion_main!(benches);
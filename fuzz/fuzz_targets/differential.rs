@@ -0,0 +1,44 @@
+//! Checks this crate against the RustCrypto `sha1` crate on arbitrary
+//! input, through the one-shot, streaming and whole-block entry points.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sha1::digest::generic_array::GenericArray;
+use sha1::{Digest, Sha1 as Reference};
+
+fuzz_target!(|data: &[u8]| {
+    let expected: [u8; 20] = Reference::digest(data).into();
+
+    assert_eq!(bad_sha1::hash(data), expected, "hash");
+
+    // Streaming, with chunk lengths taken from the input itself so the
+    // fuzzer controls where block boundaries fall. A zero length becomes an
+    // empty update followed by a single byte, so the loop always advances.
+    let mut sha1 = bad_sha1::Sha1::new();
+    let mut rest = data;
+    let mut i = 0;
+    while !rest.is_empty() {
+        let mut n = data[i % data.len()] as usize % 130;
+        if n == 0 {
+            sha1.update(&[]);
+            n = 1;
+        }
+        let n = n.min(rest.len());
+        sha1.update(&rest[..n]);
+        rest = &rest[n..];
+        i += 1;
+    }
+    assert_eq!(sha1.finalize(), expected, "streaming");
+
+    // The unpadded whole-block path, against the reference compression
+    // function.
+    let aligned = &data[..data.len() - data.len() % 64];
+    let blocks: Vec<_> = aligned
+        .chunks_exact(64)
+        .map(GenericArray::clone_from_slice)
+        .collect();
+    let mut state = bad_sha1::consts::HASH_CONSTANTS;
+    sha1::compress(&mut state, &blocks);
+    assert_eq!(bad_sha1::hash_blocks(aligned), state, "hash_blocks");
+});
@@ -0,0 +1,27 @@
+#ifndef BAD_SHA1_H
+#define BAD_SHA1_H
+
+/* Generated with cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Streaming SHA-1 hasher, for input that is not available all at once.
+typedef struct bad_sha1_ctx bad_sha1_ctx;
+
+// Hashes `len` bytes at `ptr` and writes the 20 byte digest to `out`.
+void bad_sha1_hash(const uint8_t *ptr, size_t len, uint8_t *out);
+
+// Allocates a streaming hasher, to be released with [`bad_sha1_free`].
+bad_sha1_ctx *bad_sha1_new(void);
+
+// Feeds `len` bytes at `ptr` into the hasher `ctx`.
+void bad_sha1_update(bad_sha1_ctx *ctx, const uint8_t *ptr, size_t len);
+
+// Writes the 20 byte digest to `out` and resets `ctx` for reuse.
+void bad_sha1_finalize(bad_sha1_ctx *ctx, uint8_t *out);
+
+// Releases a hasher allocated by [`bad_sha1_new`].
+void bad_sha1_free(bad_sha1_ctx *ctx);
+
+#endif  /* BAD_SHA1_H */
@@ -0,0 +1,114 @@
+use crate::consts::ROUND_CONSTANTS;
+use core::arch::aarch64::*;
+#[cfg(target_endian = "big")]
+use core::convert::TryInto;
+
+/// Returns whether the CPU supports the SHA-1 crypto extensions.
+///
+/// The SHA-1 instructions are reported under the `sha2` capability.
+pub(crate) fn sha1_available() -> bool {
+    std::arch::is_aarch64_feature_detected!("sha2")
+}
+
+/// Alternative to the scalar `update_hash` using the `sha1c`, `sha1p`,
+/// `sha1m`, `sha1h` and `sha1su0/1` instructions.
+///
+/// Each step runs four rounds. The schedule vector for step `g >= 4` is
+/// derived from those of the previous four steps, which are kept in `msg`
+/// indexed by `g % 4`.
+///
+/// # Safety
+///
+/// The CPU must support the `sha2` and `neon` features, see
+/// [`sha1_available`].
+#[target_feature(enable = "sha2,neon")]
+pub(crate) unsafe fn update_hash(hash: &mut [u32; 5], block: &[u8]) {
+    let block = &block[..64];
+
+    let abcd_start = vld1q_u32(hash.as_ptr());
+    let e_start = hash[4];
+
+    let mut msg = load_message(block);
+
+    let mut abcd = abcd_start;
+    let mut e = e_start;
+
+    for g in 0..20 {
+        if g >= 4 {
+            msg[g % 4] = vsha1su1q_u32(
+                vsha1su0q_u32(msg[g % 4], msg[(g + 1) % 4], msg[(g + 2) % 4]),
+                msg[(g + 3) % 4],
+            );
+        }
+
+        let wk = vaddq_u32(msg[g % 4], vdupq_n_u32(ROUND_CONSTANTS[g / 5]));
+        let next_e = vsha1h_u32(vgetq_lane_u32(abcd, 0));
+        abcd = match g / 5 {
+            0 => vsha1cq_u32(abcd, e, wk),
+            2 => vsha1mq_u32(abcd, e, wk),
+            _ => vsha1pq_u32(abcd, e, wk),
+        };
+        e = next_e;
+    }
+
+    vst1q_u32(hash.as_mut_ptr(), vaddq_u32(abcd_start, abcd));
+    hash[4] = e_start.wrapping_add(e);
+}
+
+/// Loads the block as sixteen big-endian message words, four per vector.
+#[cfg(target_endian = "little")]
+#[inline(always)]
+unsafe fn load_message(block: &[u8]) -> [uint32x4_t; 4] {
+    [
+        vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.as_ptr()))),
+        vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.as_ptr().add(16)))),
+        vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.as_ptr().add(32)))),
+        vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.as_ptr().add(48)))),
+    ]
+}
+
+/// Loads the block as sixteen big-endian message words, four per vector.
+///
+/// Byte swapping the lanes as on little-endian targets relies on their lane
+/// layout, so the words are assembled explicitly instead.
+#[cfg(target_endian = "big")]
+#[inline(always)]
+unsafe fn load_message(block: &[u8]) -> [uint32x4_t; 4] {
+    let mut words = [0u32; 16];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+
+    [
+        vld1q_u32(words.as_ptr()),
+        vld1q_u32(words.as_ptr().add(4)),
+        vld1q_u32(words.as_ptr().add(8)),
+        vld1q_u32(words.as_ptr().add(12)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sha1_available, update_hash};
+    use crate::{update_hash_soft, HASH_CONSTANTS};
+
+    #[test]
+    fn test_matches_scalar() {
+        if !sha1_available() {
+            return;
+        }
+
+        let mut block = [0u8; 64];
+        let mut expected = HASH_CONSTANTS;
+        let mut actual = HASH_CONSTANTS;
+
+        for round in 0..100u8 {
+            for (i, byte) in block.iter_mut().enumerate() {
+                *byte = (i as u8).wrapping_mul(round).wrapping_add(round);
+            }
+            update_hash_soft(&mut expected, &block);
+            unsafe { update_hash(&mut actual, &block) };
+            assert_eq!(actual, expected);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::io::READ_BUFFER_SIZE;
+use crate::Sha1;
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Hashes everything read from an asynchronous `reader` until end of file
+/// using SHA-1.
+///
+/// This is the async counterpart of [`hash_reader`](crate::hash_reader) and
+/// behaves the same way, awaiting each read instead of blocking. The read
+/// buffer lives on the heap to keep the returned future small.
+///
+/// # Arguments
+///
+/// *  `reader` - Source of the message to hash
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::hash_async;
+/// use hex_literal::hex;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut reader: &[u8] = b"The quick brown fox jumps over the lazy dog";
+///
+/// assert_eq!(
+///     hash_async(&mut reader).await.unwrap(),
+///     hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"),
+/// );
+/// # });
+/// ```
+///
+pub async fn hash_async<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<[u8; 20]> {
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    let mut sha1 = Sha1::new();
+
+    loop {
+        match reader.read(&mut buffer).await {
+            Ok(0) => return Ok(sha1.finalize()),
+            Ok(n) => sha1.update(&buffer[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, hash_async};
+
+    #[tokio::test]
+    async fn test_hash_async() {
+        let input: Vec<u8> = (0..150_000u32).map(|i| (i * 7) as u8).collect();
+
+        assert_eq!(hash_async(&mut &input[..]).await.unwrap(), hash(&input));
+        assert_eq!(
+            hash_async(&mut tokio::io::empty()).await.unwrap(),
+            hash(b"")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hash_async_chunked() {
+        let (mut rx, mut tx) = tokio::io::simplex(16);
+        let input = [0x42u8; 1000];
+
+        let writer = tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt;
+            for chunk in input.chunks(33) {
+                tx.write_all(chunk).await.unwrap();
+            }
+            tx.shutdown().await.unwrap();
+        });
+        let digest = hash_async(&mut rx).await.unwrap();
+        writer.await.unwrap();
+
+        assert_eq!(digest, hash(input));
+    }
+}
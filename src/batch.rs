@@ -0,0 +1,158 @@
+use crate::{hash, verify, Sha1};
+use alloc::vec::Vec;
+use rayon::prelude::*;
+
+/// Hashes each of the given messages using SHA-1, spreading the work across
+/// the rayon thread pool. The digests are returned in the same order as the
+/// inputs.
+///
+/// # Arguments
+///
+/// *  `inputs` - Slice of byte slices holding the input messages
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_batch};
+///
+/// let digests = hash_batch(&[b"abc", b"def"]);
+///
+/// assert_eq!(digests, [hash(b"abc"), hash(b"def")]);
+/// ```
+///
+pub fn hash_batch(inputs: &[&[u8]]) -> Vec<[u8; 20]> {
+    inputs.par_iter().map(hash).collect()
+}
+
+/// Checks each message against its expected SHA-1 digest, hashing them in
+/// parallel on the rayon thread pool. Every comparison is constant time as
+/// in [`verify`](crate::verify), and the results are returned in the same
+/// order as the pairs.
+///
+/// # Arguments
+///
+/// *  `pairs` - Slice of messages paired with the digests they should have
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, verify_batch};
+///
+/// let pairs: [(&[u8], [u8; 20]); 2] = [(b"abc", hash(b"abc")), (b"def", hash(b"abc"))];
+///
+/// assert_eq!(verify_batch(&pairs), [true, false]);
+/// ```
+///
+pub fn verify_batch(pairs: &[(&[u8], [u8; 20])]) -> Vec<bool> {
+    pairs
+        .par_iter()
+        .map(|(input, expected)| verify(input, expected))
+        .collect()
+}
+
+/// Computes a two level SHA-1 hash tree over `input`, splitting it into
+/// `chunk_size` byte chunks that are hashed in parallel on the rayon thread
+/// pool. The root is the SHA-1 of the concatenated chunk digests.
+///
+/// This is a separate construction, so the result differs from
+/// [`hash`](crate::hash) of the same input and from any other tree hash
+/// format. It is meant for checking the integrity of large buffers with
+/// this crate on both ends, not for interoperability. The chunk size is part
+/// of the result, so both sides must agree on it.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+/// *  `chunk_size` - Length of each leaf chunk, the last one may be shorter
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, tree_hash};
+///
+/// let input = vec![0x5a; 1 << 20];
+/// let root = tree_hash(&input, 64 * 1024);
+///
+/// assert_eq!(root, tree_hash(&input, 64 * 1024));
+/// assert_ne!(root, hash(&input));
+/// ```
+///
+pub fn tree_hash(input: &[u8], chunk_size: usize) -> [u8; 20] {
+    assert!(chunk_size > 0, "tree_hash chunk size must be nonzero");
+
+    let leaves: Vec<[u8; 20]> = input.par_chunks(chunk_size).map(hash).collect();
+
+    let mut root = Sha1::new();
+    for leaf in leaves.iter() {
+        root.update(leaf);
+    }
+    root.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, hash_batch, tree_hash, verify_batch};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_hash_batch_order() {
+        let messages: Vec<Vec<u8>> = (0..1000u32)
+            .map(|i| i.to_le_bytes().repeat(i as usize % 37))
+            .collect();
+        let inputs: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+
+        let digests = hash_batch(&inputs);
+
+        assert_eq!(digests.len(), inputs.len());
+        for (input, digest) in inputs.iter().zip(digests.iter()) {
+            assert_eq!(*digest, hash(input));
+        }
+        assert!(hash_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let messages: Vec<Vec<u8>> = (0..500u32).map(|i| i.to_be_bytes().repeat(7)).collect();
+        let pairs: Vec<(&[u8], [u8; 20])> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let mut digest = hash(m);
+                if i % 3 == 0 {
+                    digest[19] ^= 1;
+                }
+                (m.as_slice(), digest)
+            })
+            .collect();
+
+        let results = verify_batch(&pairs);
+
+        assert_eq!(results.len(), pairs.len());
+        for (i, ok) in results.iter().enumerate() {
+            assert_eq!(*ok, i % 3 != 0);
+        }
+        assert!(verify_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_tree_hash() {
+        let input: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+
+        let leaves: Vec<u8> = input.chunks(1000).flat_map(hash).collect();
+        assert_eq!(tree_hash(&input, 1000), hash(&leaves));
+
+        // A single chunk is still hashed a second time.
+        assert_eq!(tree_hash(&input, 1 << 20), hash(hash(&input)));
+        assert_eq!(tree_hash(b"", 64), hash(b""));
+    }
+
+    #[test]
+    #[should_panic(expected = "nonzero")]
+    fn test_tree_hash_zero_chunk() {
+        tree_hash(b"abc", 0);
+    }
+}
@@ -0,0 +1,324 @@
+use crate::consts::{HASH_CONSTANTS, ROUND_CONSTANTS};
+use crate::{padding_blocks, write_digest, Sha1Error};
+use core::convert::TryInto;
+
+mod ubc_check;
+
+use ubc_check::{ubc_check, Step, SHA1_DVS};
+
+// First 320 bytes of the SHAttered PDFs (Stevens et al., 2017): a shared
+// 192 byte prefix followed by the two near-collision blocks, which differ.
+const SHATTERED_PREFIX: [u8; 192] = [
+    0x25, 0x50, 0x44, 0x46, 0x2d, 0x31, 0x2e, 0x33, 0x0a, 0x25, 0xe2, 0xe3, 0xcf, 0xd3, 0x0a, 0x0a,
+    0x0a, 0x31, 0x20, 0x30, 0x20, 0x6f, 0x62, 0x6a, 0x0a, 0x3c, 0x3c, 0x2f, 0x57, 0x69, 0x64, 0x74,
+    0x68, 0x20, 0x32, 0x20, 0x30, 0x20, 0x52, 0x2f, 0x48, 0x65, 0x69, 0x67, 0x68, 0x74, 0x20, 0x33,
+    0x20, 0x30, 0x20, 0x52, 0x2f, 0x54, 0x79, 0x70, 0x65, 0x20, 0x34, 0x20, 0x30, 0x20, 0x52, 0x2f,
+    0x53, 0x75, 0x62, 0x74, 0x79, 0x70, 0x65, 0x20, 0x35, 0x20, 0x30, 0x20, 0x52, 0x2f, 0x46, 0x69,
+    0x6c, 0x74, 0x65, 0x72, 0x20, 0x36, 0x20, 0x30, 0x20, 0x52, 0x2f, 0x43, 0x6f, 0x6c, 0x6f, 0x72,
+    0x53, 0x70, 0x61, 0x63, 0x65, 0x20, 0x37, 0x20, 0x30, 0x20, 0x52, 0x2f, 0x4c, 0x65, 0x6e, 0x67,
+    0x74, 0x68, 0x20, 0x38, 0x20, 0x30, 0x20, 0x52, 0x2f, 0x42, 0x69, 0x74, 0x73, 0x50, 0x65, 0x72,
+    0x43, 0x6f, 0x6d, 0x70, 0x6f, 0x6e, 0x65, 0x6e, 0x74, 0x20, 0x38, 0x3e, 0x3e, 0x0a, 0x73, 0x74,
+    0x72, 0x65, 0x61, 0x6d, 0x0a, 0xff, 0xd8, 0xff, 0xfe, 0x00, 0x24, 0x53, 0x48, 0x41, 0x2d, 0x31,
+    0x20, 0x69, 0x73, 0x20, 0x64, 0x65, 0x61, 0x64, 0x21, 0x21, 0x21, 0x21, 0x21, 0x85, 0x2f, 0xec,
+    0x09, 0x23, 0x39, 0x75, 0x9c, 0x39, 0xb1, 0xa1, 0xc6, 0x3c, 0x4c, 0x97, 0xe1, 0xff, 0xfe, 0x01,
+];
+const SHATTERED_BLOCKS_1: [u8; 128] = [
+    0x73, 0x46, 0xdc, 0x91, 0x66, 0xb6, 0x7e, 0x11, 0x8f, 0x02, 0x9a, 0xb6, 0x21, 0xb2, 0x56, 0x0f,
+    0xf9, 0xca, 0x67, 0xcc, 0xa8, 0xc7, 0xf8, 0x5b, 0xa8, 0x4c, 0x79, 0x03, 0x0c, 0x2b, 0x3d, 0xe2,
+    0x18, 0xf8, 0x6d, 0xb3, 0xa9, 0x09, 0x01, 0xd5, 0xdf, 0x45, 0xc1, 0x4f, 0x26, 0xfe, 0xdf, 0xb3,
+    0xdc, 0x38, 0xe9, 0x6a, 0xc2, 0x2f, 0xe7, 0xbd, 0x72, 0x8f, 0x0e, 0x45, 0xbc, 0xe0, 0x46, 0xd2,
+    0x3c, 0x57, 0x0f, 0xeb, 0x14, 0x13, 0x98, 0xbb, 0x55, 0x2e, 0xf5, 0xa0, 0xa8, 0x2b, 0xe3, 0x31,
+    0xfe, 0xa4, 0x80, 0x37, 0xb8, 0xb5, 0xd7, 0x1f, 0x0e, 0x33, 0x2e, 0xdf, 0x93, 0xac, 0x35, 0x00,
+    0xeb, 0x4d, 0xdc, 0x0d, 0xec, 0xc1, 0xa8, 0x64, 0x79, 0x0c, 0x78, 0x2c, 0x76, 0x21, 0x56, 0x60,
+    0xdd, 0x30, 0x97, 0x91, 0xd0, 0x6b, 0xd0, 0xaf, 0x3f, 0x98, 0xcd, 0xa4, 0xbc, 0x46, 0x29, 0xb1,
+];
+const SHATTERED_BLOCKS_2: [u8; 128] = [
+    0x7f, 0x46, 0xdc, 0x93, 0xa6, 0xb6, 0x7e, 0x01, 0x3b, 0x02, 0x9a, 0xaa, 0x1d, 0xb2, 0x56, 0x0b,
+    0x45, 0xca, 0x67, 0xd6, 0x88, 0xc7, 0xf8, 0x4b, 0x8c, 0x4c, 0x79, 0x1f, 0xe0, 0x2b, 0x3d, 0xf6,
+    0x14, 0xf8, 0x6d, 0xb1, 0x69, 0x09, 0x01, 0xc5, 0x6b, 0x45, 0xc1, 0x53, 0x0a, 0xfe, 0xdf, 0xb7,
+    0x60, 0x38, 0xe9, 0x72, 0x72, 0x2f, 0xe7, 0xad, 0x72, 0x8f, 0x0e, 0x49, 0x04, 0xe0, 0x46, 0xc2,
+    0x30, 0x57, 0x0f, 0xe9, 0xd4, 0x13, 0x98, 0xab, 0xe1, 0x2e, 0xf5, 0xbc, 0x94, 0x2b, 0xe3, 0x35,
+    0x42, 0xa4, 0x80, 0x2d, 0x98, 0xb5, 0xd7, 0x0f, 0x2a, 0x33, 0x2e, 0xc3, 0x7f, 0xac, 0x35, 0x14,
+    0xe7, 0x4d, 0xdc, 0x0f, 0x2c, 0xc1, 0xa8, 0x74, 0xcd, 0x0c, 0x78, 0x30, 0x5a, 0x21, 0x56, 0x64,
+    0x61, 0x30, 0x97, 0x89, 0x60, 0x6b, 0xd0, 0xbf, 0x3f, 0x98, 0xcd, 0xa8, 0x04, 0x46, 0x29, 0xa1,
+];
+
+/// Hashes the given input using SHA-1 while checking every block for the
+/// signature of a cryptanalytic collision attack, in the style of Stevens
+/// and Shumow's sha1collisiondetection (as used by Git and GitHub).
+///
+/// Each block is tested against the disturbance vectors used by the known
+/// practical attacks, including SHAttered and Shambles. If a block looks
+/// like one half of an attack pair, [`Sha1Error::CollisionDetected`] is
+/// returned. For any other input this returns the same digest as
+/// [`crate::hash`]; the chance of a false positive is below 2^-90.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_with_collision_detection};
+///
+/// assert_eq!(hash_with_collision_detection(b"abc"), Ok(hash(b"abc")));
+/// ```
+///
+pub fn hash_with_collision_detection(input: &[u8]) -> Result<[u8; 20], Sha1Error> {
+    let mut state = HASH_CONSTANTS;
+
+    let mut blocks = input.chunks_exact(64);
+    for block in blocks.by_ref() {
+        if compress_checked(&mut state, block.try_into().unwrap()) {
+            return Err(Sha1Error::CollisionDetected);
+        }
+    }
+
+    let mut last = [[0u8; 64]; 2];
+    let count = padding_blocks(blocks.remainder(), input.len() as u64, &mut last);
+    for block in last[..count].iter() {
+        if compress_checked(&mut state, block) {
+            return Err(Sha1Error::CollisionDetected);
+        }
+    }
+
+    let mut out = [0u8; 20];
+    write_digest(&state, &mut out);
+    Ok(out)
+}
+
+/// Returns whether `input` begins with either of the two colliding
+/// prefixes published with the SHAttered attack.
+///
+/// This is a cheap byte comparison against the 320 bytes the SHAttered PDFs
+/// start with: a shared 192 byte header followed by one of the two 128 byte
+/// near-collision block pairs. It only recognizes that one published
+/// collision, not new ones built the same way; use
+/// [`hash_with_collision_detection`] for that.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+///
+pub fn is_known_collision_prefix(input: &[u8]) -> bool {
+    if input.len() < 320 || input[..192] != SHATTERED_PREFIX {
+        return false;
+    }
+    let blocks = &input[192..320];
+    blocks == SHATTERED_BLOCKS_1 || blocks == SHATTERED_BLOCKS_2
+}
+
+/// Hashes the given input using SHA-1, unless it starts with one of the
+/// published SHAttered collision prefixes.
+///
+/// See [`is_known_collision_prefix`] for exactly what is refused. Other
+/// input gets the same digest as [`crate::hash`].
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+///
+/// # Errors
+///
+/// Returns [`Sha1Error::CollisionDetected`] if the input starts with a
+/// SHAttered prefix.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_checked};
+///
+/// assert_eq!(hash_checked(b"abc"), Ok(hash(b"abc")));
+/// ```
+///
+pub fn hash_checked(input: &[u8]) -> Result<[u8; 20], Sha1Error> {
+    if is_known_collision_prefix(input) {
+        return Err(Sha1Error::CollisionDetected);
+    }
+    Ok(crate::hash(input))
+}
+
+/// Runs the compression function on `block` like [`crate::compress`], then
+/// returns whether the block is one half of a collision for any of the
+/// disturbance vectors in [`SHA1_DVS`].
+fn compress_checked(state: &mut [u32; 5], block: &[u8; 64]) -> bool {
+    let mut w = [0u32; 80];
+    for (word, bytes) in w.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    for t in 16..80 {
+        w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+    }
+
+    let ihv = *state;
+    let mut vars = ihv;
+    let mut state_58 = [0; 5];
+    let mut state_65 = [0; 5];
+
+    for t in 0..80 {
+        match t {
+            58 => state_58 = vars,
+            65 => state_65 = vars,
+            _ => {}
+        }
+        step(&mut vars, &w, t);
+    }
+    add_state(state, &ihv, &vars);
+
+    let mask = ubc_check(&w);
+    if mask == 0 {
+        return false;
+    }
+
+    for (i, dv) in SHA1_DVS.iter().enumerate() {
+        if mask & (1 << i) == 0 {
+            continue;
+        }
+
+        let mut w2 = w;
+        for (w, dm) in w2.iter_mut().zip(dv.dm.iter()) {
+            *w ^= dm;
+        }
+
+        let (start, vars) = match dv.step {
+            Step::S58 => (58, state_58),
+            Step::S65 => (65, state_65),
+        };
+
+        // If the differences of this disturbance vector cancel out by step
+        // `start`, the sibling block shares the intermediate state there.
+        // Unwind it back to the sibling's input chaining value and forward
+        // to its output; a match means the two blocks collide.
+        let mut ihv2 = vars;
+        for t in (0..start).rev() {
+            unstep(&mut ihv2, &w2, t);
+        }
+        let mut vars2 = vars;
+        for t in start..80 {
+            step(&mut vars2, &w2, t);
+        }
+
+        let mut out2 = [0; 5];
+        add_state(&mut out2, &ihv2, &vars2);
+        if out2 == *state {
+            return true;
+        }
+    }
+    false
+}
+
+fn round_function(t: usize, b: u32, c: u32, d: u32) -> u32 {
+    match t / 20 {
+        0 => (b & c) | (!b & d),
+        2 => (b & c) | (b & d) | (c & d),
+        _ => b ^ c ^ d,
+    }
+}
+
+/// Applies step `t` of the compression function to the working variables.
+#[inline(always)]
+fn step(vars: &mut [u32; 5], w: &[u32; 80], t: usize) {
+    let [a, b, c, d, e] = *vars;
+    let temp = a
+        .rotate_left(5)
+        .wrapping_add(round_function(t, b, c, d))
+        .wrapping_add(e)
+        .wrapping_add(ROUND_CONSTANTS[t / 20])
+        .wrapping_add(w[t]);
+
+    *vars = [temp, a, b.rotate_left(30), c, d];
+}
+
+/// Inverse of [`step`], recovering the working variables before step `t`.
+#[inline(always)]
+fn unstep(vars: &mut [u32; 5], w: &[u32; 80], t: usize) {
+    let [temp, a, b, c, d] = *vars;
+    let b = b.rotate_right(30);
+    let e = temp.wrapping_sub(
+        a.rotate_left(5)
+            .wrapping_add(round_function(t, b, c, d))
+            .wrapping_add(ROUND_CONSTANTS[t / 20])
+            .wrapping_add(w[t]),
+    );
+
+    *vars = [a, b, c, d, e];
+}
+
+fn add_state(out: &mut [u32; 5], ihv: &[u32; 5], vars: &[u32; 5]) {
+    for ((o, h), v) in out.iter_mut().zip(ihv.iter()).zip(vars.iter()) {
+        *o = h.wrapping_add(*v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SHATTERED_BLOCKS_1, SHATTERED_BLOCKS_2, SHATTERED_PREFIX};
+    use crate::{
+        hash, hash_checked, hash_with_collision_detection, is_known_collision_prefix, Sha1Error,
+    };
+
+    fn shattered(blocks: &[u8; 128]) -> [u8; 320] {
+        let mut out = [0u8; 320];
+        out[..192].copy_from_slice(&SHATTERED_PREFIX);
+        out[192..].copy_from_slice(blocks);
+        out
+    }
+
+    #[test]
+    fn test_shattered_detected() {
+        let first = shattered(&SHATTERED_BLOCKS_1);
+        let second = shattered(&SHATTERED_BLOCKS_2);
+
+        // The two inputs really do collide under plain SHA-1
+        assert_ne!(first[..], second[..]);
+        assert_eq!(hash(first), hash(second));
+
+        assert_eq!(
+            hash_with_collision_detection(&first),
+            Err(Sha1Error::CollisionDetected)
+        );
+        assert_eq!(
+            hash_with_collision_detection(&second),
+            Err(Sha1Error::CollisionDetected)
+        );
+
+        // The shared prefix on its own is harmless
+        assert_eq!(
+            hash_with_collision_detection(&SHATTERED_PREFIX),
+            Ok(hash(SHATTERED_PREFIX))
+        );
+    }
+
+    #[test]
+    fn test_known_prefix() {
+        for blocks in [SHATTERED_BLOCKS_1, SHATTERED_BLOCKS_2].iter() {
+            let mut pdf = [0u8; 400];
+            pdf[..320].copy_from_slice(&shattered(blocks));
+
+            assert!(is_known_collision_prefix(&pdf[..320]));
+            assert!(is_known_collision_prefix(&pdf));
+            assert_eq!(hash_checked(&pdf), Err(Sha1Error::CollisionDetected));
+
+            // Truncated or altered prefixes are let through
+            assert!(!is_known_collision_prefix(&pdf[..319]));
+            pdf[200] ^= 1;
+            assert!(!is_known_collision_prefix(&pdf));
+            assert_eq!(hash_checked(&pdf), Ok(hash(pdf)));
+        }
+
+        assert!(!is_known_collision_prefix(&SHATTERED_PREFIX));
+        assert_eq!(hash_checked(b""), Ok(hash(b"")));
+    }
+
+    #[test]
+    fn test_ordinary_inputs() {
+        let data = [0x3cu8; 1000];
+        for len in [0, 1, 63, 64, 65, 200, 1000].iter() {
+            assert_eq!(
+                hash_with_collision_detection(&data[..*len]),
+                Ok(hash(&data[..*len])),
+            );
+        }
+    }
+}
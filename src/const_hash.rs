@@ -0,0 +1,150 @@
+use crate::consts::{HASH_CONSTANTS, ROUND_CONSTANTS};
+
+/// Hashes the given input using SHA-1 at compile time, returning the same
+/// digest as [`hash`](crate::hash).
+///
+/// This is a `const fn` so it can be used to initialise constants and
+/// statics. It is noticeably slower than [`hash`](crate::hash), so prefer
+/// the latter for runtime hashing.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::hash_const;
+/// use hex_literal::hex;
+///
+/// const DIGEST: [u8; 20] = hash_const(b"The quick brown fox jumps over the lazy dog");
+///
+/// assert_eq!(DIGEST, hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"));
+/// ```
+///
+pub const fn hash_const(input: &[u8]) -> [u8; 20] {
+    let mut hash = HASH_CONSTANTS;
+
+    let mut offset = 0;
+    while input.len() - offset >= 64 {
+        hash = compress_const(hash, input, offset);
+        offset += 64;
+    }
+
+    let rem_len = input.len() - offset;
+
+    let mut last_block = [0u8; 64];
+    let mut i = 0;
+    while i < rem_len {
+        last_block[i] = input[offset + i];
+        i += 1;
+    }
+    last_block[rem_len] = 0x80;
+
+    if rem_len > 55 {
+        hash = compress_const(hash, &last_block, 0);
+        last_block = [0u8; 64];
+    }
+
+    let bit_length = (input.len() as u64).wrapping_mul(8).to_be_bytes();
+    let mut i = 0;
+    while i < 8 {
+        last_block[56 + i] = bit_length[i];
+        i += 1;
+    }
+    hash = compress_const(hash, &last_block, 0);
+
+    let mut output = [0u8; 20];
+    let mut word = 0;
+    while word < 5 {
+        output[word * 4] = (hash[word] >> 24) as u8;
+        output[word * 4 + 1] = (hash[word] >> 16) as u8;
+        output[word * 4 + 2] = (hash[word] >> 8) as u8;
+        output[word * 4 + 3] = hash[word] as u8;
+        word += 1;
+    }
+    output
+}
+
+/// `const` counterpart of `update_hash`, processing the 64 byte block that
+/// starts at `offset` in `input`.
+const fn compress_const(hash: [u32; 5], input: &[u8], offset: usize) -> [u32; 5] {
+    let mut w = [0u32; 80];
+
+    let mut t = 0;
+    while t < 16 {
+        let i = offset + t * 4;
+        w[t] = (input[i] as u32) << 24;
+        w[t] |= (input[i + 1] as u32) << 16;
+        w[t] |= (input[i + 2] as u32) << 8;
+        w[t] |= input[i + 3] as u32;
+        t += 1;
+    }
+
+    while t < 80 {
+        w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+        t += 1;
+    }
+
+    let mut a = hash[0];
+    let mut b = hash[1];
+    let mut c = hash[2];
+    let mut d = hash[3];
+    let mut e = hash[4];
+
+    let mut t = 0;
+    while t < 80 {
+        let (f, k) = match t / 20 {
+            0 => ((b & c) | (!b & d), ROUND_CONSTANTS[0]),
+            1 => (b ^ c ^ d, ROUND_CONSTANTS[1]),
+            2 => ((b & c) | (b & d) | (c & d), ROUND_CONSTANTS[2]),
+            _ => (b ^ c ^ d, ROUND_CONSTANTS[3]),
+        };
+
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(w[t])
+            .wrapping_add(k);
+
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+        t += 1;
+    }
+
+    [
+        hash[0].wrapping_add(a),
+        hash[1].wrapping_add(b),
+        hash[2].wrapping_add(c),
+        hash[3].wrapping_add(d),
+        hash[4].wrapping_add(e),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, hash_const};
+    use hex_literal::hex;
+
+    #[test]
+    fn test_const_context() {
+        const ABC: [u8; 20] = hash_const(b"abc");
+        const EMPTY: [u8; 20] = hash_const(b"");
+
+        assert_eq!(ABC, hash(b"abc"));
+        assert_eq!(EMPTY, hex!("da39a3ee5e6b4b0d3255bfef95601890afd80709"));
+    }
+
+    #[test]
+    fn test_matches_runtime() {
+        let input = [0xa5u8; 200];
+
+        for len in 0..input.len() {
+            assert_eq!(hash_const(&input[..len]), hash(&input[..len]));
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! The constants defined by FIPS 180-4 for SHA-1.
+
+/// The size in bytes of the blocks SHA-1 processes, also available as
+/// [`Sha1::BLOCK_SIZE`](crate::Sha1::BLOCK_SIZE).
+///
+pub const BLOCK_SIZE: usize = 64;
+
+/// The size in bytes of a SHA-1 digest, also available as
+/// [`Sha1::OUTPUT_SIZE`](crate::Sha1::OUTPUT_SIZE).
+///
+pub const OUTPUT_SIZE: usize = 20;
+
+/// The initial hash value `H(0)`, loaded into the state before the first
+/// block is compressed.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{consts::HASH_CONSTANTS, Sha1};
+///
+/// let sha1 = Sha1::with_iv(HASH_CONSTANTS);
+/// assert_eq!(sha1.finalize(), bad_sha1::hash(b""));
+/// ```
+///
+pub const HASH_CONSTANTS: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+/// The round constants `K`, one per group of 20 rounds, so round `t` uses
+/// `ROUND_CONSTANTS[t / 20]`.
+///
+pub const ROUND_CONSTANTS: [u32; 4] = [0x5A827999, 0x6ED9EBA1, 0x8F1BBCDC, 0xCA62C1D6];
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::ROUND_CONSTANTS;
+
+    #[test]
+    fn test_round_constants() {
+        // Each constant is floor(2^30 * sqrt(n)) for n = 2, 3, 5 and 10.
+        for (n, &k) in [2.0f64, 3.0, 5.0, 10.0].iter().zip(ROUND_CONSTANTS.iter()) {
+            assert_eq!((n.sqrt() * (1u64 << 30) as f64) as u32, k);
+        }
+    }
+}
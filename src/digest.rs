@@ -0,0 +1,597 @@
+use crate::encoding::{decode_base32, decode_base64, decode_hex, encode_hex, HEX_LOWER, HEX_UPPER};
+#[cfg(feature = "alloc")]
+use crate::encoding::{encode_base32, encode_base64};
+use crate::verify::ct_eq;
+use crate::{hash_iter, Sha1Error};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::array::TryFromSliceError;
+use core::convert::{TryFrom, TryInto};
+use core::fmt;
+use core::iter::FromIterator;
+use core::ops::Deref;
+use core::str::{self, FromStr};
+
+/// A 20 byte SHA-1 digest.
+///
+/// Formats as 40 hexadecimal characters with `{}`, `{:x}` and `{:X}`, and
+/// orders and hashes like the underlying bytes so it can be used as a map
+/// key.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::hash_digest;
+///
+/// let digest = hash_digest(b"The quick brown fox jumps over the lazy dog");
+///
+/// assert_eq!(
+///     format!("{}", digest),
+///     "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12",
+/// );
+/// assert_eq!(
+///     format!("{:X}", digest),
+///     "2FD4E1C67A2D28FCED849EE1BB76E7391B93EB12",
+/// );
+/// ```
+///
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Digest([u8; 20]);
+
+impl Digest {
+    /// Hashes `data` using SHA-1 and wraps the result, the same as
+    /// [`hash_digest`] but for anything that can be viewed as bytes.
+    ///
+    /// This computes the digest *of* the bytes. To reinterpret 20 bytes as
+    /// an existing digest, use `Digest::from([u8; 20])` or
+    /// `Digest::try_from(&[u8])` instead.
+    ///
+    /// # Arguments
+    ///
+    /// *  `data` - The input message
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::{hash, Digest};
+    ///
+    /// assert_eq!(Digest::of(b"abc".as_slice()), hash(b"abc"));
+    /// assert_eq!(Digest::of("abc"), Digest::from("abc"));
+    /// ```
+    ///
+    pub fn of<T: AsRef<[u8]>>(data: T) -> Digest {
+        hash_digest(data.as_ref())
+    }
+
+    /// Compares two digests in constant time.
+    ///
+    /// Unlike `==`, this always looks at all 20 bytes, so it doesn't leak
+    /// the position of the first difference through timing.
+    ///
+    /// # Arguments
+    ///
+    /// *  `other` - The digest to compare against
+    ///
+    pub fn ct_eq(&self, other: &Digest) -> bool {
+        ct_eq(&self.0, &other.0)
+    }
+
+    /// Writes the digest as 40 lowercase hexadecimal ASCII characters into
+    /// `out`, without allocating.
+    ///
+    /// # Arguments
+    ///
+    /// *  `out` - Buffer the hexadecimal characters are written to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::hash_digest;
+    ///
+    /// let mut buf = [0u8; 40];
+    /// hash_digest(b"").encode_hex(&mut buf);
+    ///
+    /// assert_eq!(&buf, b"da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    /// ```
+    ///
+    pub fn encode_hex(&self, out: &mut [u8; 40]) {
+        encode_hex(&self.0, out, HEX_LOWER);
+    }
+
+    /// Writes the digest as 40 uppercase hexadecimal ASCII characters into
+    /// `out`, without allocating.
+    ///
+    /// # Arguments
+    ///
+    /// *  `out` - Buffer the hexadecimal characters are written to
+    ///
+    pub fn encode_hex_upper(&self, out: &mut [u8; 40]) {
+        encode_hex(&self.0, out, HEX_UPPER);
+    }
+
+    /// Encodes the digest as 28 characters of standard base64, including
+    /// the trailing `=` padding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::hash_digest;
+    ///
+    /// assert_eq!(hash_digest(b"").to_base64(), "2jmj7l5rSw0yVb/vlWAYkK/YBwk=");
+    /// ```
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn to_base64(&self) -> String {
+        let mut out = [0u8; 28];
+        encode_base64(&self.0, &mut out);
+        out.iter().map(|&c| c as char).collect()
+    }
+
+    /// Parses a digest from 28 characters of standard base64, as produced
+    /// by [`Digest::to_base64`]. The padding is required.
+    ///
+    /// # Arguments
+    ///
+    /// *  `s` - The base64 string to parse
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::{hash_digest, Digest};
+    ///
+    /// let digest = Digest::from_base64("2jmj7l5rSw0yVb/vlWAYkK/YBwk=").unwrap();
+    ///
+    /// assert_eq!(digest, hash_digest(b""));
+    /// ```
+    ///
+    pub fn from_base64(s: &str) -> Result<Self, Sha1Error> {
+        let mut bytes = [0u8; 20];
+        decode_base64(s.as_bytes(), &mut bytes)?;
+        Ok(Digest(bytes))
+    }
+
+    /// Encodes the digest as 32 characters of uppercase RFC 4648 base32.
+    /// 20 bytes fill the last group exactly, so there is never any padding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::hash_digest;
+    ///
+    /// assert_eq!(hash_digest(b"").to_base32(), "3I42H3S6NNFQ2MSVX7XZKYAYSCX5QBYJ");
+    /// ```
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn to_base32(&self) -> String {
+        let mut out = [0u8; 32];
+        encode_base32(&self.0, &mut out);
+        out.iter().map(|&c| c as char).collect()
+    }
+
+    /// Parses a digest from 32 characters of RFC 4648 base32, in either
+    /// case.
+    ///
+    /// # Arguments
+    ///
+    /// *  `s` - The base32 string to parse
+    ///
+    pub fn from_base32(s: &str) -> Result<Self, Sha1Error> {
+        let mut bytes = [0u8; 20];
+        decode_base32(s.as_bytes(), &mut bytes)?;
+        Ok(Digest(bytes))
+    }
+
+    fn fmt_hex(&self, f: &mut fmt::Formatter, alphabet: &[u8; 16]) -> fmt::Result {
+        let mut out = [0u8; 40];
+        encode_hex(&self.0, &mut out, alphabet);
+        f.pad(str::from_utf8(&out).unwrap())
+    }
+}
+
+impl From<[u8; 20]> for Digest {
+    fn from(bytes: [u8; 20]) -> Self {
+        Digest(bytes)
+    }
+}
+
+impl From<Digest> for [u8; 20] {
+    fn from(digest: Digest) -> Self {
+        digest.0
+    }
+}
+
+/// Hashes the string's UTF-8 bytes, the same as [`hash_digest`].
+///
+/// Unlike [`str::parse`], which decodes an existing digest from 40
+/// hexadecimal characters, this computes the digest *of* the string.
+/// For byte slices use [`Digest::of`]: a `From<&[u8]>` impl would clash
+/// with `TryFrom<&[u8]>`, which reinterprets 20 bytes as a digest.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, Digest};
+///
+/// assert_eq!(Digest::from("abc"), hash(b"abc"));
+/// assert_eq!(
+///     Digest::from("abc").to_string(),
+///     "a9993e364706816aba3e25717850c26c9cd0d89d",
+/// );
+/// ```
+///
+impl From<&str> for Digest {
+    fn from(s: &str) -> Self {
+        hash_digest(s.as_bytes())
+    }
+}
+
+/// Hashes the bytes produced by an iterator, the same as [`hash_iter`].
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, Digest};
+///
+/// let digest: Digest = b"abc".iter().copied().collect();
+///
+/// assert_eq!(digest, hash(b"abc"));
+/// ```
+///
+impl FromIterator<u8> for Digest {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        Digest(hash_iter(iter))
+    }
+}
+
+/// Fails unless the slice is exactly 20 bytes long.
+impl TryFrom<&[u8]> for Digest {
+    type Error = TryFromSliceError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Digest(bytes.try_into()?))
+    }
+}
+
+impl AsRef<[u8]> for Digest {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Deref for Digest {
+    type Target = [u8; 20];
+
+    fn deref(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl PartialEq<[u8; 20]> for Digest {
+    fn eq(&self, other: &[u8; 20]) -> bool {
+        self.0 == *other
+    }
+}
+
+/// Compares against a hex string in either case. Strings that are not
+/// valid 40 character hex compare unequal.
+impl PartialEq<str> for Digest {
+    fn eq(&self, other: &str) -> bool {
+        other.parse::<Digest>().is_ok_and(|d| d == *self)
+    }
+}
+
+impl PartialEq<&str> for Digest {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Digest({})", self)
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_hex(f, HEX_LOWER)
+    }
+}
+
+impl fmt::LowerHex for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_hex(f, HEX_LOWER)
+    }
+}
+
+impl fmt::UpperHex for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_hex(f, HEX_UPPER)
+    }
+}
+
+/// Parses a digest from 40 hexadecimal characters, in either case.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash_digest, Digest};
+///
+/// let digest: Digest = "2FD4E1C67A2D28FCED849EE1BB76E7391B93EB12".parse().unwrap();
+///
+/// assert_eq!(digest, hash_digest(b"The quick brown fox jumps over the lazy dog"));
+/// ```
+///
+impl FromStr for Digest {
+    type Err = Sha1Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0u8; 20];
+        decode_hex(s.as_bytes(), &mut bytes)?;
+        Ok(Digest(bytes))
+    }
+}
+
+/// Hashes the given input using SHA-1, returning the digest wrapped in a
+/// [`Digest`].
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+///
+pub fn hash_digest(input: &[u8]) -> Digest {
+    Digest(crate::hash(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, hash_digest, Digest, Sha1Error};
+    use core::convert::TryFrom;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_conversions() {
+        let bytes = hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12");
+        let digest = Digest::from(bytes);
+
+        assert_eq!(
+            digest,
+            hash_digest(b"The quick brown fox jumps over the lazy dog")
+        );
+        assert_eq!(digest.as_ref(), &bytes[..]);
+        assert_eq!(<[u8; 20]>::from(digest), bytes);
+        assert_eq!(*digest, bytes);
+        assert_eq!(digest.len(), 20);
+    }
+
+    #[test]
+    fn test_try_from_slice() {
+        let bytes = hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12");
+
+        assert_eq!(Digest::try_from(&bytes[..]).unwrap(), Digest::from(bytes));
+        assert!(Digest::try_from(&bytes[..19]).is_err());
+        assert!(Digest::try_from(&[0u8; 21][..]).is_err());
+        assert!(Digest::try_from(&[][..]).is_err());
+    }
+
+    #[test]
+    fn test_of() {
+        let bytes = [0x42u8; 20];
+
+        assert_eq!(Digest::of(b"abc".as_slice()), hash(b"abc"));
+        assert_eq!(Digest::of(b"abc"), hash(b"abc"));
+        assert_eq!(Digest::of(&bytes[..]), hash(bytes));
+        // Hashing, not reinterpreting like `try_from`
+        assert_ne!(Digest::of(bytes), Digest::try_from(&bytes[..]).unwrap());
+    }
+
+    #[test]
+    fn test_from_str_hashes() {
+        assert_eq!(Digest::from("abc"), hash(b"abc"));
+        assert_eq!(Digest::from(""), hash_digest(b""));
+
+        // Hashing, not decoding: a hex string becomes the digest of its text
+        let hex = "a9993e364706816aba3e25717850c26c9cd0d89d";
+        assert_ne!(Digest::from(hex), hex.parse::<Digest>().unwrap());
+        assert_eq!(Digest::from(hex), hash(hex.as_bytes()));
+    }
+
+    #[test]
+    fn test_collect() {
+        let digest: Digest = b"abc".iter().copied().collect();
+        assert_eq!(digest, hash(b"abc"));
+
+        let empty: Digest = core::iter::empty().collect();
+        assert_eq!(empty, hash(b""));
+    }
+
+    #[test]
+    fn test_partial_eq() {
+        let digest = hash_digest(b"The quick brown fox jumps over the lazy dog");
+
+        assert_eq!(digest, hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"));
+        assert_ne!(digest, [0u8; 20]);
+        assert_eq!(digest, "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12");
+        assert!(digest == *"2FD4E1C67A2D28FCED849EE1BB76E7391B93EB12");
+        assert_ne!(digest, "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_ne!(digest, "2fd4e1c67a2d28fced849ee1bb76e7391b93eb1");
+        assert_ne!(digest, "2fd4e1c67a2d28fced849ee1bb76e7391b93eb1z");
+    }
+
+    #[test]
+    fn test_ordering() {
+        let low = Digest::from([0u8; 20]);
+        let high = Digest::from(hash(b""));
+
+        assert!(low < high);
+        assert_eq!(low.max(high), high);
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        let digest = hash_digest(b"abc");
+
+        assert!(digest.ct_eq(&hash_digest(b"abc")));
+        assert!(!digest.ct_eq(&hash_digest(b"abd")));
+    }
+
+    #[test]
+    fn test_parse() {
+        let expected = hash_digest(b"The quick brown fox jumps over the lazy dog");
+
+        assert_eq!(
+            "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12".parse(),
+            Ok(expected)
+        );
+        assert_eq!(
+            "2FD4E1C67A2D28FCED849EE1BB76E7391B93EB12".parse(),
+            Ok(expected)
+        );
+        assert_eq!(
+            "2fd4E1C67a2d28fced849ee1bb76e7391b93eb12".parse(),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<Digest>(), Err(Sha1Error::BadEncodingLength));
+        assert_eq!(
+            "2fd4e1c67a2d28fced849ee1bb76e7391b93eb1".parse::<Digest>(),
+            Err(Sha1Error::BadEncodingLength),
+        );
+        assert_eq!(
+            "2fd4e1c67a2d28fced849ee1bb76e7391b93eb120".parse::<Digest>(),
+            Err(Sha1Error::BadEncodingLength),
+        );
+        assert_eq!(
+            "2fd4e1c67a2d28fced849ee1bb76e7391b93eb1g".parse::<Digest>(),
+            Err(Sha1Error::BadEncodingChar),
+        );
+        assert_eq!(
+            "+fd4e1c67a2d28fced849ee1bb76e7391b93eb12".parse::<Digest>(),
+            Err(Sha1Error::BadEncodingChar),
+        );
+        // 40 bytes but only 39 characters.
+        assert_eq!(
+            "éd4e1c67a2d28fced849ee1bb76e7391b93eb12".parse::<Digest>(),
+            Err(Sha1Error::BadEncodingChar),
+        );
+    }
+
+    #[test]
+    fn test_encode_hex() {
+        let digest = hash_digest(b"The quick brown fox jumps over the lazy dog");
+        let mut buf = [0u8; 40];
+
+        digest.encode_hex(&mut buf);
+        assert_eq!(&buf, b"2fd4e1c67a2d28fced849ee1bb76e7391b93eb12");
+
+        digest.encode_hex_upper(&mut buf);
+        assert_eq!(&buf, b"2FD4E1C67A2D28FCED849EE1BB76E7391B93EB12");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_hex_matches_hash_hex() {
+        let input = b"abc";
+        let mut buf = [0u8; 40];
+        hash_digest(input).encode_hex(&mut buf);
+
+        assert_eq!(&buf[..], crate::hash_hex(input).as_bytes());
+    }
+
+    #[test]
+    fn test_from_base64() {
+        assert_eq!(
+            Digest::from_base64("L9ThxnotKPzthJ7hu3bnORuT6xI="),
+            Ok(hash_digest(b"The quick brown fox jumps over the lazy dog")),
+        );
+
+        assert_eq!(
+            Digest::from_base64("L9ThxnotKPzthJ7hu3bnORuT6xI"),
+            Err(Sha1Error::BadEncodingLength),
+        );
+        assert_eq!(
+            Digest::from_base64("L9ThxnotKPzthJ7hu3bnORuT6xI=="),
+            Err(Sha1Error::BadEncodingLength),
+        );
+        assert_eq!(
+            Digest::from_base64("L9ThxnotKPzthJ7hu3bnORuT6x!="),
+            Err(Sha1Error::BadEncodingChar),
+        );
+        assert_eq!(
+            Digest::from_base64("L9ThxnotKPzthJ7hu3bnORuT==I="),
+            Err(Sha1Error::BadEncodingChar),
+        );
+        // Last character carries bits past the end of the digest
+        assert_eq!(
+            Digest::from_base64("L9ThxnotKPzthJ7hu3bnORuT6xJ="),
+            Err(Sha1Error::BadEncodingChar),
+        );
+    }
+
+    #[test]
+    fn test_from_base32() {
+        let expected = hash_digest(b"The quick brown fox jumps over the lazy dog");
+
+        assert_eq!(
+            Digest::from_base32("F7KODRT2FUUPZ3MET3Q3W5XHHENZH2YS"),
+            Ok(expected)
+        );
+        assert_eq!(
+            Digest::from_base32("f7kodrt2fuupz3met3q3w5xhhenzh2ys"),
+            Ok(expected)
+        );
+        assert_eq!(
+            Digest::from_base32("F7KODRT2FUUPZ3MET3Q3W5XHHENZH2Y"),
+            Err(Sha1Error::BadEncodingLength),
+        );
+        assert_eq!(
+            Digest::from_base32("F7KODRT2FUUPZ3MET3Q3W5XHHENZH2Y1"),
+            Err(Sha1Error::BadEncodingChar),
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_round_trip() {
+        for input in [
+            &b""[..],
+            b"abc",
+            b"The quick brown fox jumps over the lazy dog",
+        ]
+        .iter()
+        {
+            let digest = hash_digest(input);
+            assert_eq!(Digest::from_base64(&digest.to_base64()), Ok(digest));
+            assert_eq!(Digest::from_base32(&digest.to_base32()), Ok(digest));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_formatting() {
+        use alloc::format;
+
+        let digest = hash_digest(b"");
+
+        assert_eq!(
+            format!("{}", digest),
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+        assert_eq!(
+            format!("{:x}", digest),
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+        assert_eq!(
+            format!("{:X}", digest),
+            "DA39A3EE5E6B4B0D3255BFEF95601890AFD80709"
+        );
+        assert_eq!(
+            format!("{:?}", digest),
+            "Digest(da39a3ee5e6b4b0d3255bfef95601890afd80709)"
+        );
+        assert_eq!(format!("{:>42}", digest), format!("  {}", digest));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::Sha1;
+use ::digest::consts::U20;
+use ::digest::generic_array::GenericArray;
+use ::digest::{FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update};
+
+impl Sha1 {
+    /// Pads the message, processes the final block(s) and returns the
+    /// 20 byte digest as a `GenericArray`, the output type used by the
+    /// RustCrypto crates. [`Sha1::finalize`] is otherwise the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::{hash, Sha1};
+    ///
+    /// let mut sha1 = Sha1::new();
+    /// sha1.update(b"abc");
+    ///
+    /// assert_eq!(sha1.finalize_generic()[..], hash(b"abc"));
+    /// ```
+    ///
+    pub fn finalize_generic(self) -> GenericArray<u8, U20> {
+        self.finalize().into()
+    }
+}
+
+/// Lets `Sha1` be used wherever the RustCrypto traits are expected, most
+/// notably through the blanket `digest::Digest` impl.
+impl HashMarker for Sha1 {}
+
+impl OutputSizeUser for Sha1 {
+    type OutputSize = U20;
+}
+
+impl Update for Sha1 {
+    fn update(&mut self, data: &[u8]) {
+        Sha1::update(self, data);
+    }
+}
+
+impl FixedOutput for Sha1 {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        Sha1::finalize_into(self, out.as_mut());
+    }
+}
+
+impl Reset for Sha1 {
+    fn reset(&mut self) {
+        Sha1::reset(self);
+    }
+}
+
+impl FixedOutputReset for Sha1 {
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.finalize_reset());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sha1;
+    use ::digest::Digest;
+    use hex_literal::hex;
+
+    fn generic_hash<D: Digest>(input: &[u8]) -> ::digest::Output<D> {
+        let mut hasher = D::new();
+        hasher.update(input);
+        hasher.finalize()
+    }
+
+    #[test]
+    fn test_digest_trait() {
+        assert_eq!(
+            generic_hash::<Sha1>(b"abc")[..],
+            hex!("a9993e364706816aba3e25717850c26c9cd0d89d"),
+        );
+        assert_eq!(<Sha1 as Digest>::output_size(), 20);
+    }
+
+    #[test]
+    fn test_digest_reset() {
+        let mut hasher = <Sha1 as Digest>::new();
+        Digest::update(&mut hasher, b"garbage");
+        Digest::reset(&mut hasher);
+        Digest::update(&mut hasher, b"abc");
+        assert_eq!(
+            hasher.finalize_reset()[..],
+            hex!("a9993e364706816aba3e25717850c26c9cd0d89d"),
+        );
+        assert_eq!(hasher.finalize()[..], crate::hash(b"")[..]);
+    }
+
+    #[test]
+    fn test_finalize_generic() {
+        let mut sha1 = Sha1::new();
+        sha1.update(b"abc");
+        let generic: ::digest::Output<Sha1> = sha1.finalize_generic();
+        assert_eq!(generic[..], crate::hash(b"abc"));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{Sha1, Sha1Error};
+
+/// Hashes `input` with `salt` prepended using SHA-1, giving the digest of
+/// `salt || input` without building the concatenation.
+///
+/// Useful for cache keys, where a tool version salt makes identical content
+/// map to different keys across versions. This is plain concatenation, so
+/// `("ab", "c")` and `("a", "bc")` give the same digest; the salt should be
+/// fixed length or otherwise unambiguous.
+///
+/// # Arguments
+///
+/// *  `salt` - Byte slice hashed before the input
+/// *  `input` - Byte slice holding input message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_salted};
+///
+/// let key = hash_salted(b"mytool-1.2.0:", b"file contents");
+///
+/// assert_eq!(key, hash(b"mytool-1.2.0:file contents"));
+/// assert_ne!(key, hash_salted(b"mytool-1.3.0:", b"file contents"));
+/// ```
+///
+pub fn hash_salted(salt: &[u8], input: &[u8]) -> [u8; 20] {
+    let mut sha1 = Sha1::new();
+    sha1.update(salt);
+    sha1.update(input);
+    sha1.finalize()
+}
+
+/// Hashes `input` under a domain separation tag using SHA-1, giving the
+/// digest of `[domain.len() as u8] || domain || input`.
+///
+/// The length byte makes the encoding prefix-free, so inputs hashed under
+/// different domains can never produce the same hashed bytes, even when one
+/// domain is a prefix of the other.
+///
+/// # Arguments
+///
+/// *  `domain` - Byte slice holding the domain tag, at most 255 bytes
+/// *  `input` - Byte slice holding input message
+///
+/// # Errors
+///
+/// Returns [`Sha1Error::DomainTooLong`] if `domain` is longer than 255
+/// bytes.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_domain};
+///
+/// let digest = hash_domain(b"user-id", b"alice").unwrap();
+///
+/// assert_eq!(digest, hash(b"\x07user-idalice"));
+/// assert_ne!(digest, hash_domain(b"session", b"alice").unwrap());
+/// ```
+///
+pub fn hash_domain(domain: &[u8], input: &[u8]) -> Result<[u8; 20], Sha1Error> {
+    if domain.len() > u8::MAX as usize {
+        return Err(Sha1Error::DomainTooLong);
+    }
+
+    let mut sha1 = Sha1::new();
+    sha1.update(&[domain.len() as u8]);
+    sha1.update(domain);
+    sha1.update(input);
+    Ok(sha1.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, hash_domain, hash_salted, Sha1Error};
+
+    #[test]
+    fn test_hash_salted() {
+        let input = [0x11u8; 200];
+        let mut joined = [0u8; 263];
+        joined[..63].copy_from_slice(&[0x22; 63]);
+        joined[63..].copy_from_slice(&input);
+
+        assert_eq!(hash_salted(&[0x22; 63], &input), hash(&joined[..]));
+        assert_eq!(hash_salted(b"", &input), hash(input));
+        assert_eq!(hash_salted(b"abc", b""), hash(b"abc"));
+    }
+
+    #[test]
+    fn test_hash_domain() {
+        let a = hash_domain(b"a", b"bc").unwrap();
+        let ab = hash_domain(b"ab", b"c").unwrap();
+
+        assert_ne!(a, ab);
+        assert_ne!(a, hash_domain(b"b", b"bc").unwrap());
+        assert_eq!(a, hash(b"\x01abc"));
+        assert_eq!(hash_domain(b"", b"").unwrap(), hash([0u8]));
+
+        assert!(hash_domain(&[0; 255], b"").is_ok());
+        assert_eq!(hash_domain(&[0; 256], b""), Err(Sha1Error::DomainTooLong));
+    }
+}
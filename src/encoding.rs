@@ -0,0 +1,277 @@
+use crate::Sha1Error;
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec};
+
+pub(crate) const HEX_LOWER: &[u8; 16] = b"0123456789abcdef";
+pub(crate) const HEX_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Writes the hexadecimal representation of `bytes` into `out`, which must
+/// be exactly twice as long, using the given digit alphabet.
+pub(crate) fn encode_hex(bytes: &[u8], out: &mut [u8], alphabet: &[u8; 16]) {
+    for (byte, pair) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+        pair[0] = alphabet[(byte >> 4) as usize];
+        pair[1] = alphabet[(byte & 0xf) as usize];
+    }
+}
+
+/// Parses the hexadecimal string `hex` into `out`, which must be exactly
+/// half as long. Both upper and lower case digits are accepted.
+pub(crate) fn decode_hex(hex: &[u8], out: &mut [u8]) -> Result<(), Sha1Error> {
+    if hex.len() != out.len() * 2 {
+        return Err(Sha1Error::BadEncodingLength);
+    }
+
+    for (byte, pair) in out.iter_mut().zip(hex.chunks_exact(2)) {
+        *byte = (decode_hex_digit(pair[0])? << 4) | decode_hex_digit(pair[1])?;
+    }
+    Ok(())
+}
+
+fn decode_hex_digit(digit: u8) -> Result<u8, Sha1Error> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(Sha1Error::BadEncodingChar),
+    }
+}
+
+#[cfg(feature = "alloc")]
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Writes the standard base64 representation of `bytes` into `out`, padded
+/// with `=` to a multiple of four characters. `out` must be exactly that
+/// long.
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_base64(bytes: &[u8], out: &mut [u8]) {
+    debug_assert_eq!(out.len(), bytes.len().div_ceil(3) * 4);
+
+    for (chunk, quad) in bytes.chunks(3).zip(out.chunks_exact_mut(4)) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let n = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+
+        for (i, c) in quad.iter_mut().enumerate() {
+            *c = if i <= chunk.len() {
+                BASE64[(n >> (18 - 6 * i) & 0x3f) as usize]
+            } else {
+                b'='
+            };
+        }
+    }
+}
+
+/// Parses the padded standard base64 string `b64` into `out`. Padding in
+/// the wrong place and non-zero bits past the end of the data are rejected,
+/// so each output has exactly one accepted encoding.
+pub(crate) fn decode_base64(b64: &[u8], out: &mut [u8]) -> Result<(), Sha1Error> {
+    if b64.len() != out.len().div_ceil(3) * 4 {
+        return Err(Sha1Error::BadEncodingLength);
+    }
+
+    for (chunk, quad) in out.chunks_mut(3).zip(b64.chunks_exact(4)) {
+        let mut n = 0u32;
+        for (i, &c) in quad.iter().enumerate() {
+            let value = if i <= chunk.len() {
+                decode_base64_digit(c)?
+            } else if c == b'=' {
+                0
+            } else {
+                return Err(Sha1Error::BadEncodingChar);
+            };
+            n = (n << 6) | value as u32;
+        }
+
+        let group = n.to_be_bytes();
+        if group[1 + chunk.len()..].iter().any(|&b| b != 0) {
+            return Err(Sha1Error::BadEncodingChar);
+        }
+        chunk.copy_from_slice(&group[1..1 + chunk.len()]);
+    }
+    Ok(())
+}
+
+fn decode_base64_digit(digit: u8) -> Result<u8, Sha1Error> {
+    match digit {
+        b'A'..=b'Z' => Ok(digit - b'A'),
+        b'a'..=b'z' => Ok(digit - b'a' + 26),
+        b'0'..=b'9' => Ok(digit - b'0' + 52),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(Sha1Error::BadEncodingChar),
+    }
+}
+
+#[cfg(feature = "alloc")]
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Writes the unpadded RFC 4648 base32 representation of `bytes` into
+/// `out`, which must be exactly `ceil(bytes.len() * 8 / 5)` long.
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_base32(bytes: &[u8], out: &mut [u8]) {
+    debug_assert_eq!(out.len(), (bytes.len() * 8).div_ceil(5));
+
+    let mut buffer = 0u16;
+    let mut bits = 0;
+    let mut out = out.iter_mut();
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            *out.next().unwrap() = BASE32[(buffer >> bits & 0x1f) as usize];
+        }
+    }
+    if bits > 0 {
+        *out.next().unwrap() = BASE32[(buffer << (5 - bits) & 0x1f) as usize];
+    }
+}
+
+/// Parses the unpadded base32 string `b32` into `out`. Both upper and lower
+/// case letters are accepted, but non-zero bits past the end of the data
+/// are rejected.
+pub(crate) fn decode_base32(b32: &[u8], out: &mut [u8]) -> Result<(), Sha1Error> {
+    if b32.len() != (out.len() * 8).div_ceil(5) {
+        return Err(Sha1Error::BadEncodingLength);
+    }
+
+    let mut buffer = 0u16;
+    let mut bits = 0;
+    let mut out = out.iter_mut();
+
+    for &c in b32 {
+        buffer = (buffer << 5) | decode_base32_digit(c)? as u16;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            *out.next().unwrap() = (buffer >> bits) as u8;
+        }
+    }
+    if buffer & ((1 << bits) - 1) != 0 {
+        return Err(Sha1Error::BadEncodingChar);
+    }
+    Ok(())
+}
+
+fn decode_base32_digit(digit: u8) -> Result<u8, Sha1Error> {
+    match digit {
+        b'A'..=b'Z' => Ok(digit - b'A'),
+        b'a'..=b'z' => Ok(digit - b'a'),
+        b'2'..=b'7' => Ok(digit - b'2' + 26),
+        _ => Err(Sha1Error::BadEncodingChar),
+    }
+}
+
+/// Encodes the given bytes as unpadded, uppercase RFC 4648 base32, the form
+/// authenticator apps use for TOTP secrets.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding the data to encode
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::base32_encode;
+///
+/// assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+/// ```
+///
+#[cfg(feature = "alloc")]
+pub fn base32_encode(input: &[u8]) -> String {
+    let mut out = vec![0u8; (input.len() * 8).div_ceil(5)];
+    encode_base32(input, &mut out);
+    out.iter().map(|&c| c as char).collect()
+}
+
+/// Hashes the given input using SHA-1, returning the digest as 40 ASCII
+/// lowercase hexadecimal digits in a fixed array, for `no_std` code that
+/// has no heap.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::hash_hex_bytes;
+///
+/// assert_eq!(
+///     &hash_hex_bytes(b"The quick brown fox jumps over the lazy dog"),
+///     b"2fd4e1c67a2d28fced849ee1bb76e7391b93eb12",
+/// );
+/// ```
+///
+pub fn hash_hex_bytes(input: &[u8]) -> [u8; 40] {
+    let mut out = [0u8; 40];
+    encode_hex(&crate::hash(input), &mut out, HEX_LOWER);
+    out
+}
+
+/// Hashes the given input using SHA-1, returning the digest as a 40
+/// character lowercase hexadecimal string, as printed by `sha1sum`.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::hash_hex;
+///
+/// assert_eq!(
+///     hash_hex(b"The quick brown fox jumps over the lazy dog"),
+///     "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12",
+/// );
+/// ```
+///
+#[cfg(feature = "alloc")]
+pub fn hash_hex(input: &[u8]) -> String {
+    hash_hex_bytes(input).iter().map(|&c| c as char).collect()
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{base32_encode, hash_hex, hash_hex_bytes};
+
+    #[test]
+    fn test_hash_hex() {
+        assert_eq!(hash_hex(b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(
+            hash_hex(b"The quick brown fox jumps over the lazy cog"),
+            "de9f2c7fd25e1b3afad3e85a0bd17d9b100db4b3",
+        );
+    }
+
+    #[test]
+    fn test_hash_hex_bytes() {
+        for input in [&b""[..], b"abc", &[0xff; 1000]].iter() {
+            assert_eq!(&hash_hex_bytes(input)[..], hash_hex(input).as_bytes());
+        }
+    }
+
+    // RFC 4648 section 10, without the padding.
+    #[test]
+    fn test_base32_encode() {
+        let vectors = [
+            (&b""[..], ""),
+            (b"f", "MY"),
+            (b"fo", "MZXQ"),
+            (b"foo", "MZXW6"),
+            (b"foob", "MZXW6YQ"),
+            (b"fooba", "MZXW6YTB"),
+            (b"foobar", "MZXW6YTBOI"),
+        ];
+
+        for (input, expected) in vectors.iter() {
+            assert_eq!(base32_encode(input), *expected);
+        }
+        assert_eq!(
+            base32_encode(b"12345678901234567890"),
+            "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ",
+        );
+    }
+}
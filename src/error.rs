@@ -0,0 +1,84 @@
+use core::fmt;
+
+/// The error type shared by every fallible operation in this crate.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{Digest, Sha1Error};
+///
+/// let err = "not a digest".parse::<Digest>().unwrap_err();
+///
+/// assert_eq!(err, Sha1Error::BadEncodingLength);
+/// assert_eq!(err.to_string(), "encoded digest has the wrong length");
+/// ```
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Sha1Error {
+    /// The input is longer than [`MAX_INPUT_LEN`](crate::MAX_INPUT_LEN)
+    /// bytes, returned by [`try_hash`](crate::try_hash).
+    InputTooLong,
+    /// A midstate byte count is not a multiple of the 64 byte block size,
+    /// returned by [`Sha1::from_midstate`](crate::Sha1::from_midstate).
+    BadMidstateLength,
+    /// More output was requested than the construction can produce, returned
+    /// by [`hkdf_expand`](crate::hkdf_expand) above
+    /// [`HKDF_MAX_OUTPUT`](crate::HKDF_MAX_OUTPUT) bytes.
+    OutputTooLong,
+    /// A domain separation tag is longer than 255 bytes, returned by
+    /// [`hash_domain`](crate::hash_domain).
+    DomainTooLong,
+    /// The input contains a block crafted by a SHA-1 collision attack,
+    /// returned by
+    /// [`hash_with_collision_detection`](crate::hash_with_collision_detection)
+    /// and [`hash_checked`](crate::hash_checked).
+    CollisionDetected,
+    /// A hexadecimal, base64 or base32 string is not the right length for
+    /// 20 bytes in its encoding.
+    BadEncodingLength,
+    /// A hexadecimal, base64 or base32 string contains a character outside
+    /// its encoding's alphabet, or misplaced padding.
+    BadEncodingChar,
+}
+
+impl fmt::Display for Sha1Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Sha1Error::InputTooLong => "input exceeds the SHA-1 limit of 2^64 - 1 bits",
+            Sha1Error::BadMidstateLength => "midstate byte count must be a multiple of 64",
+            Sha1Error::OutputTooLong => "HKDF-SHA1 output is limited to 5100 bytes",
+            Sha1Error::DomainTooLong => "domain tag is longer than 255 bytes",
+            Sha1Error::CollisionDetected => "SHA-1 collision attack detected in input",
+            Sha1Error::BadEncodingLength => "encoded digest has the wrong length",
+            Sha1Error::BadEncodingChar => "invalid character in encoded digest",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Sha1Error {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::consts::HASH_CONSTANTS;
+    use crate::{hkdf_expand, Digest, Sha1, Sha1Error};
+
+    fn parse_and_expand(hex: &str, len: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let digest: Digest = hex.parse()?;
+        Sha1::from_midstate(HASH_CONSTANTS, 64)?;
+        hkdf_expand(&digest, b"", &mut vec![0; len])?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_question_mark() {
+        let hex = "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12";
+
+        assert!(parse_and_expand(hex, 20).is_ok());
+        let err = parse_and_expand(hex, 6000).unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&Sha1Error::OutputTooLong));
+        let err = parse_and_expand("xyz", 20).unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&Sha1Error::BadEncodingLength));
+    }
+}
@@ -0,0 +1,161 @@
+//! C ABI for embedding the hasher in native applications.
+//!
+//! The header `include/bad_sha1.h` is generated from this module with
+//! `cbindgen --config cbindgen.toml --output include/bad_sha1.h`. Every
+//! function accepts null pointers and does nothing when given one; digests
+//! are written as 20 raw bytes.
+
+use crate::Sha1;
+use alloc::boxed::Box;
+use core::slice;
+
+/// Hashes `len` bytes at `ptr` and writes the 20 byte digest to `out`.
+///
+/// Does nothing if `out` is null, or if `ptr` is null and `len` is not
+/// zero.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads of `len` bytes and `out` must be valid for
+/// writes of 20 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bad_sha1_hash(ptr: *const u8, len: usize, out: *mut u8) {
+    if out.is_null() {
+        return;
+    }
+    if let Some(input) = input_slice(ptr, len) {
+        write_out(&crate::hash(input), out);
+    }
+}
+
+/// Allocates a streaming hasher, to be released with [`bad_sha1_free`].
+#[no_mangle]
+pub extern "C" fn bad_sha1_new() -> *mut Sha1 {
+    Box::into_raw(Box::new(Sha1::new()))
+}
+
+/// Feeds `len` bytes at `ptr` into the hasher `ctx`.
+///
+/// Does nothing if `ctx` is null, or if `ptr` is null and `len` is not
+/// zero.
+///
+/// # Safety
+///
+/// `ctx` must be null or a pointer returned by [`bad_sha1_new`] that has not
+/// been freed, and `ptr` must be valid for reads of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bad_sha1_update(ctx: *mut Sha1, ptr: *const u8, len: usize) {
+    if let (Some(sha1), Some(data)) = (ctx.as_mut(), input_slice(ptr, len)) {
+        sha1.update(data);
+    }
+}
+
+/// Writes the 20 byte digest to `out` and resets `ctx` for reuse.
+///
+/// Does nothing if either pointer is null.
+///
+/// # Safety
+///
+/// `ctx` must be null or a pointer returned by [`bad_sha1_new`] that has not
+/// been freed, and `out` must be valid for writes of 20 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bad_sha1_finalize(ctx: *mut Sha1, out: *mut u8) {
+    if out.is_null() {
+        return;
+    }
+    if let Some(sha1) = ctx.as_mut() {
+        write_out(&sha1.finalize_reset(), out);
+    }
+}
+
+/// Releases a hasher allocated by [`bad_sha1_new`].
+///
+/// Does nothing if `ctx` is null.
+///
+/// # Safety
+///
+/// `ctx` must be null or a pointer returned by [`bad_sha1_new`] that has not
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn bad_sha1_free(ctx: *mut Sha1) {
+    if !ctx.is_null() {
+        drop(Box::from_raw(ctx));
+    }
+}
+
+/// Builds the input slice, treating a null pointer as valid only for an
+/// empty input.
+unsafe fn input_slice<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if ptr.is_null() {
+        if len == 0 {
+            Some(&[])
+        } else {
+            None
+        }
+    } else {
+        Some(slice::from_raw_parts(ptr, len))
+    }
+}
+
+unsafe fn write_out(digest: &[u8; 20], out: *mut u8) {
+    out.copy_from_nonoverlapping(digest.as_ptr(), digest.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bad_sha1_finalize, bad_sha1_free, bad_sha1_hash, bad_sha1_new, bad_sha1_update};
+    use crate::hash;
+    use core::ptr;
+
+    #[test]
+    fn test_one_shot() {
+        let input = b"The quick brown fox jumps over the lazy dog";
+        let mut out = [0u8; 20];
+
+        unsafe { bad_sha1_hash(input.as_ptr(), input.len(), out.as_mut_ptr()) };
+        assert_eq!(out, hash(input));
+
+        unsafe { bad_sha1_hash(ptr::null(), 0, out.as_mut_ptr()) };
+        assert_eq!(out, hash(b""));
+    }
+
+    #[test]
+    fn test_streaming() {
+        let mut out = [0u8; 20];
+
+        unsafe {
+            let ctx = bad_sha1_new();
+            bad_sha1_update(ctx, b"The quick brown fox ".as_ptr(), 20);
+            bad_sha1_update(ctx, ptr::null(), 0);
+            bad_sha1_update(ctx, b"jumps over the lazy dog".as_ptr(), 23);
+            bad_sha1_finalize(ctx, out.as_mut_ptr());
+            assert_eq!(out, hash(b"The quick brown fox jumps over the lazy dog"));
+
+            // The context is reset and can be reused
+            bad_sha1_update(ctx, b"abc".as_ptr(), 3);
+            bad_sha1_finalize(ctx, out.as_mut_ptr());
+            assert_eq!(out, hash(b"abc"));
+
+            bad_sha1_free(ctx);
+        }
+    }
+
+    #[test]
+    fn test_null_pointers() {
+        let mut out = [0xeeu8; 20];
+
+        unsafe {
+            bad_sha1_hash(ptr::null(), 10, out.as_mut_ptr());
+            bad_sha1_hash(b"abc".as_ptr(), 3, ptr::null_mut());
+            bad_sha1_update(ptr::null_mut(), b"abc".as_ptr(), 3);
+            bad_sha1_finalize(ptr::null_mut(), out.as_mut_ptr());
+            bad_sha1_free(ptr::null_mut());
+
+            let ctx = bad_sha1_new();
+            bad_sha1_update(ctx, ptr::null(), 3);
+            bad_sha1_finalize(ctx, ptr::null_mut());
+            bad_sha1_free(ctx);
+        }
+        assert_eq!(out, [0xee; 20]);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::Sha1;
+
+/// The kinds of object stored in a git repository.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GitObjectKind {
+    /// File contents.
+    Blob,
+    /// A directory listing.
+    Tree,
+    /// A commit.
+    Commit,
+    /// An annotated tag.
+    Tag,
+}
+
+impl GitObjectKind {
+    fn name(self) -> &'static [u8] {
+        match self {
+            GitObjectKind::Blob => b"blob",
+            GitObjectKind::Tree => b"tree",
+            GitObjectKind::Commit => b"commit",
+            GitObjectKind::Tag => b"tag",
+        }
+    }
+}
+
+/// Computes the git object ID of a blob, as printed by `git hash-object`.
+///
+/// # Arguments
+///
+/// *  `content` - Byte slice holding the file contents
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::git_blob_oid;
+/// use hex_literal::hex;
+///
+/// assert_eq!(
+///     git_blob_oid(b""),
+///     hex!("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+/// );
+/// ```
+///
+pub fn git_blob_oid(content: &[u8]) -> [u8; 20] {
+    git_object_oid(GitObjectKind::Blob, content)
+}
+
+/// Computes the git object ID of an object of any kind, which is the SHA-1
+/// of `"<kind> <decimal length>\0"` followed by the serialized object.
+///
+/// `content` must already be in git's canonical encoding, as printed by
+/// `git cat-file <kind> <oid>`: binary tree entries sorted by name, commit
+/// and tag headers separated by `\n`, and so on. It is hashed as given, so
+/// anything else produces an ID git will not recognise.
+///
+/// # Arguments
+///
+/// *  `kind` - The type of object
+/// *  `content` - Byte slice holding the serialized object, without header
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{git_object_oid, GitObjectKind};
+/// use hex_literal::hex;
+///
+/// // The empty tree
+/// assert_eq!(
+///     git_object_oid(GitObjectKind::Tree, b""),
+///     hex!("4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+/// );
+/// ```
+///
+pub fn git_object_oid(kind: GitObjectKind, content: &[u8]) -> [u8; 20] {
+    // u64::MAX has 20 decimal digits
+    let mut digits = [0u8; 20];
+    let mut start = digits.len();
+    let mut len = content.len() as u64;
+    loop {
+        start -= 1;
+        digits[start] = b'0' + (len % 10) as u8;
+        len /= 10;
+        if len == 0 {
+            break;
+        }
+    }
+
+    let mut sha1 = Sha1::new();
+    sha1.update(kind.name());
+    sha1.update(b" ");
+    sha1.update(&digits[start..]);
+    sha1.update(b"\0");
+    sha1.update(content);
+    sha1.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{git_blob_oid, git_object_oid, GitObjectKind};
+    use hex_literal::hex;
+
+    // Expected values from `git hash-object` and `git write-tree`.
+    #[test]
+    fn test_git_blob_oid() {
+        assert_eq!(
+            git_blob_oid(b"hello\n"),
+            hex!("ce013625030ba8dba906f756967f9e9ca394464a")
+        );
+        assert_eq!(
+            git_blob_oid(&[b'x'; 1234]),
+            hex!("72d0c78c563d4ff92840aaf19521ea20d3fa3f58")
+        );
+    }
+
+    #[test]
+    fn test_git_object_oid() {
+        let mut tree = [0u8; 37];
+        tree[..17].copy_from_slice(b"100644 hello.txt\0");
+        tree[17..].copy_from_slice(&hex!("ce013625030ba8dba906f756967f9e9ca394464a"));
+
+        assert_eq!(
+            git_object_oid(GitObjectKind::Tree, &tree),
+            hex!("aaa96ced2d9a1c8e72c56b253a0e2fe78393feb7")
+        );
+        assert_eq!(
+            git_object_oid(GitObjectKind::Commit, b""),
+            hex!("dcf5b16e76cce7425d0beaef62d79a7d10fce1f5")
+        );
+    }
+
+    // A root commit of the tree above, created with `git commit`.
+    #[test]
+    fn test_git_commit_oid() {
+        let commit = b"tree aaa96ced2d9a1c8e72c56b253a0e2fe78393feb7\n\
+            author A U Thor <author@example.com> 1700000000 +0000\n\
+            committer A U Thor <author@example.com> 1700000000 +0000\n\
+            \n\
+            Initial commit\n";
+
+        assert_eq!(
+            git_object_oid(GitObjectKind::Commit, commit),
+            hex!("e573d104f8a26d48ad380272dbb866fdadc243ea")
+        );
+    }
+}
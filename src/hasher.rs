@@ -0,0 +1,88 @@
+use crate::Sha1;
+use core::convert::TryInto;
+use core::hash::Hasher;
+
+/// A [`Hasher`] backed by SHA-1, for hash maps keyed by content.
+///
+/// `finish` returns the first 8 bytes of the digest of everything written,
+/// read as a big-endian `u64`. Unlike the standard library's default hasher
+/// the result is stable across runs, platforms and releases.
+///
+/// This is meant for spreading keys across buckets, not for security: it is
+/// unkeyed, so it gives no protection against HashDoS, and a 64-bit
+/// truncation of SHA-1 is not collision resistant.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::Sha1Hasher;
+/// use std::collections::HashMap;
+/// use std::hash::BuildHasherDefault;
+///
+/// let mut map: HashMap<&str, u32, BuildHasherDefault<Sha1Hasher>> = HashMap::default();
+/// map.insert("answer", 42);
+///
+/// assert_eq!(map.get("answer"), Some(&42));
+/// ```
+///
+#[derive(Clone)]
+pub struct Sha1Hasher {
+    sha1: Sha1,
+}
+
+impl Sha1Hasher {
+    /// Creates a hasher that has not been written to yet.
+    pub fn new() -> Self {
+        Sha1Hasher { sha1: Sha1::new() }
+    }
+}
+
+impl Default for Sha1Hasher {
+    fn default() -> Self {
+        Sha1Hasher::new()
+    }
+}
+
+impl Hasher for Sha1Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.sha1.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let digest = self.sha1.finalize();
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, Sha1Hasher};
+    use core::hash::{Hash, Hasher};
+
+    #[test]
+    fn test_finish() {
+        let mut hasher = Sha1Hasher::new();
+        hasher.write(b"The quick brown fox ");
+        hasher.write(b"jumps over the lazy dog");
+
+        // 2fd4e1c67a2d28fc... is the full digest
+        assert_eq!(hasher.finish(), 0x2fd4e1c67a2d28fc);
+
+        // finish() does not consume what was written
+        assert_eq!(hasher.finish(), 0x2fd4e1c67a2d28fc);
+        assert_eq!(Sha1Hasher::default().finish(), 0xda39a3ee5e6b4b0d);
+    }
+
+    #[test]
+    fn test_hash_trait() {
+        let mut a = Sha1Hasher::new();
+        let mut b = Sha1Hasher::new();
+        b"abc"[..].hash(&mut a);
+        b"abc"[..].hash(&mut b);
+        assert_eq!(a.finish(), b.finish());
+
+        let mut raw = Sha1Hasher::new();
+        raw.write(b"abc");
+        assert_eq!(raw.finish().to_be_bytes(), hash(b"abc")[..8]);
+    }
+}
@@ -0,0 +1,337 @@
+#[cfg(feature = "alloc")]
+use crate::encoding::{encode_hex, HEX_LOWER};
+use crate::{hash, Sha1};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+/// Computes the HMAC-SHA1 (RFC 2104) message authentication code of
+/// `message` under `key`, returning the 20 byte tag.
+///
+/// # Arguments
+///
+/// *  `key` - Byte slice holding the secret key, of any length
+/// *  `message` - Byte slice holding the message to authenticate
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::hmac;
+/// use hex_literal::hex;
+///
+/// assert_eq!(
+///     hmac(b"key", b"The quick brown fox jumps over the lazy dog"),
+///     hex!("de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"),
+/// );
+/// ```
+///
+pub fn hmac(key: &[u8], message: &[u8]) -> [u8; 20] {
+    let mut mac = HmacSha1::new(key);
+    mac.update(message);
+    mac.finalize()
+}
+
+/// Computes the HMAC-SHA1 tag of `message` under `key` and returns it as a
+/// 40 character lowercase hexadecimal string, the form most webhook
+/// providers put in their signature headers.
+///
+/// # Arguments
+///
+/// *  `key` - Byte slice holding the secret key, of any length
+/// *  `message` - Byte slice holding the message to authenticate
+///
+/// # Examples
+///
+/// Checking a GitHub-style `X-Hub-Signature` header:
+///
+/// ```
+/// use bad_sha1::hmac_hex;
+///
+/// let secret = b"key";
+/// let payload = b"The quick brown fox jumps over the lazy dog";
+/// let header = "sha1=de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9";
+///
+/// assert_eq!(header, format!("sha1={}", hmac_hex(secret, payload)));
+/// ```
+///
+#[cfg(feature = "alloc")]
+pub fn hmac_hex(key: &[u8], message: &[u8]) -> String {
+    let mut out = [0u8; 40];
+    encode_hex(&hmac(key, message), &mut out, HEX_LOWER);
+    out.iter().map(|&c| c as char).collect()
+}
+
+/// Streaming HMAC-SHA1, the incremental counterpart of [`hmac`].
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hmac, HmacSha1};
+///
+/// let mut mac = HmacSha1::new(b"key");
+/// mac.update(b"The quick brown fox ");
+/// mac.update(b"jumps over the lazy dog");
+///
+/// assert_eq!(
+///     mac.finalize(),
+///     hmac(b"key", b"The quick brown fox jumps over the lazy dog"),
+/// );
+/// ```
+///
+/// With the `zeroize` feature enabled both keyed hashers are scrubbed when
+/// the MAC is dropped, along with the padded key used to set them up.
+#[derive(Clone)]
+pub struct HmacSha1 {
+    inner: Sha1,
+    outer: Sha1,
+}
+
+impl HmacSha1 {
+    /// Creates a new HMAC-SHA1 instance keyed with `key`.
+    ///
+    /// Keys longer than the 64 byte block size are hashed first, shorter
+    /// keys are zero-padded.
+    ///
+    /// # Arguments
+    ///
+    /// *  `key` - Byte slice holding the secret key, of any length
+    ///
+    pub fn new(key: &[u8]) -> Self {
+        let mut block_key = [0u8; 64];
+        if key.len() > 64 {
+            block_key[..20].copy_from_slice(&hash(key));
+        } else {
+            block_key[..key.len()].copy_from_slice(key);
+        }
+
+        let mut pad = [0u8; 64];
+
+        for (p, k) in pad.iter_mut().zip(block_key.iter()) {
+            *p = k ^ IPAD;
+        }
+        let mut inner = Sha1::new();
+        inner.update(&pad);
+
+        for (p, k) in pad.iter_mut().zip(block_key.iter()) {
+            *p = k ^ OPAD;
+        }
+        let mut outer = Sha1::new();
+        outer.update(&pad);
+
+        #[cfg(feature = "zeroize")]
+        {
+            block_key.zeroize();
+            pad.zeroize();
+        }
+
+        HmacSha1 { inner, outer }
+    }
+
+    /// Feeds more of the message into the MAC.
+    ///
+    /// # Arguments
+    ///
+    /// *  `data` - Byte slice holding the next part of the message
+    ///
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Finishes the computation and returns the 20 byte tag.
+    pub fn finalize(mut self) -> [u8; 20] {
+        // Both hashers are finished in place rather than copied out, so the
+        // keyed states are never left behind in a temporary.
+        let inner = self.inner.finalize_reset();
+        self.outer.update(&inner);
+        self.outer.finalize_reset()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for HmacSha1 {
+    fn drop(&mut self) {
+        self.inner.zeroize();
+        self.outer.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for HmacSha1 {}
+
+/// An HMAC-SHA1 key with the inner and outer pads already absorbed.
+///
+/// [`HmacSha1::new`] pads the key and compresses it into two hasher states
+/// on every call. When many messages are authenticated under the same key,
+/// build a `HmacSha1Key` once and spawn MACs from it instead; each one
+/// starts from a copy of the precomputed states.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hmac, HmacSha1Key};
+///
+/// let key = HmacSha1Key::new(b"key");
+///
+/// for message in [&b"first"[..], b"second", b"third"].iter() {
+///     assert_eq!(key.sign(message), hmac(b"key", message));
+/// }
+///
+/// let mut mac = key.new_signer();
+/// mac.update(b"fir");
+/// mac.update(b"st");
+/// assert_eq!(mac.finalize(), key.sign(b"first"));
+/// ```
+///
+#[derive(Clone)]
+pub struct HmacSha1Key {
+    mac: HmacSha1,
+}
+
+impl HmacSha1Key {
+    /// Precomputes the inner and outer pad states for `key`.
+    ///
+    /// # Arguments
+    ///
+    /// *  `key` - Byte slice holding the secret key, of any length
+    ///
+    pub fn new(key: &[u8]) -> Self {
+        HmacSha1Key {
+            mac: HmacSha1::new(key),
+        }
+    }
+
+    /// Returns a streaming MAC under this key, ready to take the message.
+    pub fn new_signer(&self) -> HmacSha1 {
+        self.mac.clone()
+    }
+
+    /// Computes the 20 byte HMAC-SHA1 tag of `message` under this key.
+    ///
+    /// # Arguments
+    ///
+    /// *  `message` - Byte slice holding the message to authenticate
+    ///
+    pub fn sign(&self, message: &[u8]) -> [u8; 20] {
+        let mut mac = self.new_signer();
+        mac.update(message);
+        mac.finalize()
+    }
+}
+
+// The `HmacSha1` field zeroizes itself on drop.
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for HmacSha1Key {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hmac, HmacSha1, HmacSha1Key};
+    use hex_literal::hex;
+
+    // Test cases from RFC 2202 section 3.
+    #[test]
+    fn test_rfc2202() {
+        let key_long = [0xaau8; 80];
+        let key_25: [u8; 25] = hex!("0102030405060708090a0b0c0d0e0f10111213141516171819");
+        let cases: [(&[u8], &[u8], [u8; 20]); 7] = [
+            (
+                &[0x0b; 20],
+                b"Hi There",
+                hex!("b617318655057264e28bc0b6fb378c8ef146be00"),
+            ),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                hex!("effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"),
+            ),
+            (
+                &[0xaa; 20],
+                &[0xdd; 50],
+                hex!("125d7342b9ac11cd91a39af48aa17b4f63f175d3"),
+            ),
+            (
+                &key_25,
+                &[0xcd; 50],
+                hex!("4c9007f4026250c6bc8414f9bf50c86c2d7235da"),
+            ),
+            (
+                &[0x0c; 20],
+                b"Test With Truncation",
+                hex!("4c1a03424b55e07fe7f27be1d58bb9324a9a5a04"),
+            ),
+            (
+                &key_long,
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                hex!("aa4ae5e15272d00e95705637ce8a3b55ed402112"),
+            ),
+            (
+                &key_long,
+                b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data",
+                hex!("e8e99d0f45237d786d6bbaa7965c7808bbff1a91"),
+            ),
+        ];
+
+        for (key, message, expected) in cases.iter() {
+            assert_eq!(hmac(key, message), *expected);
+        }
+    }
+
+    #[test]
+    fn test_streaming() {
+        let message = b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data";
+
+        let mut mac = HmacSha1::new(&[0xaa; 80]);
+        for chunk in message.chunks(7) {
+            mac.update(chunk);
+        }
+        assert_eq!(mac.finalize(), hmac(&[0xaa; 80], message));
+    }
+
+    #[test]
+    fn test_precomputed_key() {
+        let messages: [&[u8]; 4] = [
+            b"",
+            b"Hi There",
+            &[0xdd; 50],
+            b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data",
+        ];
+
+        for key in [&b"Jefe"[..], &[0x0b; 20], &[0xaa; 80]].iter() {
+            let precomputed = HmacSha1Key::new(key);
+            for message in messages.iter() {
+                assert_eq!(precomputed.sign(message), hmac(key, message));
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_hmac_hex() {
+        // RFC 2202 test case 2.
+        assert_eq!(
+            crate::hmac_hex(b"Jefe", b"what do ya want for nothing?"),
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79",
+        );
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_on_drop() {
+        use core::mem::ManuallyDrop;
+
+        let mut mac = ManuallyDrop::new(HmacSha1::new(b"secret key"));
+        mac.update(b"message");
+
+        // The fields are plain data, so reading them after the destructor
+        // has run only observes what it left behind.
+        unsafe { ManuallyDrop::drop(&mut mac) };
+        for sha1 in [&mac.inner, &mac.outer].iter() {
+            assert_eq!(sha1.state, [0; 5]);
+            assert_eq!(sha1.block, [0; 64]);
+            assert_eq!((sha1.block_len, sha1.length), (0, 0));
+        }
+    }
+}
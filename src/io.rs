@@ -0,0 +1,656 @@
+#[cfg(feature = "mmap")]
+use crate::hash;
+use crate::Sha1;
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+
+pub(crate) const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Feeds everything written into the hasher, so any `io::Write` producer
+/// (e.g. `io::copy`) can hash its output directly.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::Sha1;
+/// use hex_literal::hex;
+/// use std::io;
+///
+/// let mut reader: &[u8] = b"The quick brown fox jumps over the lazy dog";
+/// let mut sha1 = Sha1::new();
+/// io::copy(&mut reader, &mut sha1).unwrap();
+///
+/// assert_eq!(
+///     sha1.finalize(),
+///     hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"),
+/// );
+/// ```
+///
+impl Write for Sha1 {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reader adapter that hashes every byte read through it, so a stream can
+/// be consumed and hashed in a single pass.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, HashingReader};
+/// use std::io;
+///
+/// let src: &[u8] = b"The quick brown fox jumps over the lazy dog";
+/// let mut dst = Vec::new();
+///
+/// let mut reader = HashingReader::new(src);
+/// io::copy(&mut reader, &mut dst).unwrap();
+///
+/// assert_eq!(dst, src);
+/// assert_eq!(reader.into_digest(), hash(src));
+/// ```
+///
+pub struct HashingReader<R> {
+    inner: R,
+    sha1: Sha1,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Wraps `inner`, starting a new hash.
+    ///
+    /// # Arguments
+    ///
+    /// *  `inner` - The reader to forward reads to
+    ///
+    pub fn new(inner: R) -> Self {
+        HashingReader {
+            inner,
+            sha1: Sha1::new(),
+        }
+    }
+
+    /// Returns a reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped reader. Bytes read from it
+    /// directly are not hashed.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the digest of everything read so far, dropping the reader.
+    pub fn into_digest(self) -> [u8; 20] {
+        self.sha1.finalize()
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.sha1.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Writer adapter that hashes every byte written through it, so data can be
+/// stored and hashed in a single pass.
+///
+/// Only the bytes the inner writer reports as written are hashed, so the
+/// digest always matches what actually reached it.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, HashingWriter};
+/// use std::io::Write;
+///
+/// let mut writer = HashingWriter::new(Vec::new());
+/// writer.write_all(b"The quick brown fox jumps over the lazy dog").unwrap();
+///
+/// assert_eq!(writer.get_ref(), b"The quick brown fox jumps over the lazy dog");
+/// assert_eq!(
+///     writer.into_digest(),
+///     hash(b"The quick brown fox jumps over the lazy dog"),
+/// );
+/// ```
+///
+pub struct HashingWriter<W> {
+    inner: W,
+    sha1: Sha1,
+}
+
+impl<W: Write> HashingWriter<W> {
+    /// Wraps `inner`, starting a new hash.
+    ///
+    /// # Arguments
+    ///
+    /// *  `inner` - The writer to forward writes to
+    ///
+    pub fn new(inner: W) -> Self {
+        HashingWriter {
+            inner,
+            sha1: Sha1::new(),
+        }
+    }
+
+    /// Returns a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped writer. Bytes written to
+    /// it directly are not hashed.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the digest of everything written so far, dropping the writer.
+    /// Call `flush` first if the inner writer buffers.
+    pub fn into_digest(self) -> [u8; 20] {
+        self.sha1.finalize()
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.sha1.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Hashes everything read from `reader` until end of file using SHA-1.
+///
+/// Data is read through a fixed 64 KiB stack buffer. Reads interrupted with
+/// `ErrorKind::Interrupted` are retried, any other error is returned.
+///
+/// # Arguments
+///
+/// *  `reader` - Source of the message to hash
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::hash_reader;
+/// use hex_literal::hex;
+///
+/// let mut reader: &[u8] = b"The quick brown fox jumps over the lazy dog";
+///
+/// assert_eq!(
+///     hash_reader(&mut reader).unwrap(),
+///     hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"),
+/// );
+/// ```
+///
+pub fn hash_reader<R: Read>(reader: &mut R) -> io::Result<[u8; 20]> {
+    hash_reader_with_progress(reader, |_| {})
+}
+
+/// Hashes everything read from `reader` like [`hash_reader`], calling
+/// `progress` with the total number of bytes hashed so far after each chunk.
+///
+/// The callback only runs after a read that returned data, so it is never
+/// called for an empty reader.
+///
+/// # Arguments
+///
+/// *  `reader` - Source of the message to hash
+/// *  `progress` - Callback receiving the cumulative byte count
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_reader_with_progress};
+///
+/// let mut reader: &[u8] = &[0u8; 100_000];
+/// let mut done = 0;
+///
+/// let digest = hash_reader_with_progress(&mut reader, |n| done = n).unwrap();
+/// assert_eq!(done, 100_000);
+/// assert_eq!(digest, hash(&[0u8; 100_000][..]));
+/// ```
+///
+pub fn hash_reader_with_progress<R: Read, F: FnMut(u64)>(
+    reader: &mut R,
+    progress: F,
+) -> io::Result<[u8; 20]> {
+    hash_with_buffer(reader, &mut [0u8; READ_BUFFER_SIZE], progress)
+}
+
+/// Hashes everything read from `reader` like [`hash_reader`], but reads
+/// through a heap allocated buffer of `buf_size` bytes instead of the
+/// default 64 KiB, to match the storage's preferred read size.
+///
+/// # Arguments
+///
+/// *  `reader` - Source of the message to hash
+/// *  `buf_size` - Size of the read buffer in bytes, must not be zero
+///
+/// # Errors
+///
+/// Returns an `ErrorKind::InvalidInput` error if `buf_size` is zero, and
+/// otherwise any error from `reader` other than `ErrorKind::Interrupted`.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_reader_with_capacity};
+///
+/// let mut reader: &[u8] = b"The quick brown fox jumps over the lazy dog";
+///
+/// assert_eq!(
+///     hash_reader_with_capacity(&mut reader, 1 << 20).unwrap(),
+///     hash(b"The quick brown fox jumps over the lazy dog"),
+/// );
+/// ```
+///
+pub fn hash_reader_with_capacity<R: Read>(reader: &mut R, buf_size: usize) -> io::Result<[u8; 20]> {
+    if buf_size == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "read buffer size must not be zero",
+        ));
+    }
+    hash_with_buffer(reader, &mut vec![0u8; buf_size], |_| {})
+}
+
+/// The read loop shared by the reader helpers.
+fn hash_with_buffer<R: Read, F: FnMut(u64)>(
+    reader: &mut R,
+    buffer: &mut [u8],
+    mut progress: F,
+) -> io::Result<[u8; 20]> {
+    let mut sha1 = Sha1::new();
+    let mut total = 0u64;
+
+    loop {
+        match reader.read(buffer) {
+            Ok(0) => return Ok(sha1.finalize()),
+            Ok(n) => {
+                sha1.update(&buffer[..n]);
+                total += n as u64;
+                progress(total);
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Hashes everything from a buffered reader until end of file using SHA-1,
+/// hashing straight out of its internal buffer instead of copying into
+/// another one.
+///
+/// An empty buffer from `fill_buf` marks the end of the input.
+/// `ErrorKind::Interrupted` is retried as in [`hash_reader`].
+///
+/// # Arguments
+///
+/// *  `reader` - Buffered source of the message to hash
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_bufread};
+/// use std::io::BufReader;
+///
+/// let data = [0x5au8; 10_000];
+/// let mut reader = BufReader::with_capacity(1000, &data[..]);
+///
+/// assert_eq!(hash_bufread(&mut reader).unwrap(), hash(&data[..]));
+/// ```
+///
+pub fn hash_bufread<R: BufRead>(reader: &mut R) -> io::Result<[u8; 20]> {
+    let mut sha1 = Sha1::new();
+
+    loop {
+        let n = match reader.fill_buf() {
+            Ok([]) => return Ok(sha1.finalize()),
+            Ok(buf) => {
+                sha1.update(buf);
+                buf.len()
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        reader.consume(n);
+    }
+}
+
+/// Hashes the contents of the file at `path` using SHA-1.
+///
+/// The file is streamed through [`hash_reader`], so arbitrarily large files
+/// can be hashed without loading them into memory.
+///
+/// # Arguments
+///
+/// *  `path` - Path of the file to hash
+///
+/// # Examples
+///
+/// ```no_run
+/// use bad_sha1::hash_file;
+///
+/// let digest = hash_file("Cargo.toml").unwrap();
+/// ```
+///
+pub fn hash_file<P: AsRef<Path>>(path: P) -> io::Result<[u8; 20]> {
+    hash_reader(&mut File::open(path)?)
+}
+
+/// Hashes the contents of the file at `path` using SHA-1, memory-mapping it
+/// instead of reading it through a buffer.
+///
+/// This avoids a read syscall per chunk, which pays off for very large
+/// files. Files that report a size of 0 are not mapped but read with
+/// [`hash_reader`], since procfs and sysfs entries report 0 yet have
+/// content; so is anything that is not a regular file, such as a pipe. The
+/// file must not be truncated or modified by another process while it is
+/// being hashed.
+///
+/// # Arguments
+///
+/// *  `path` - Path of the file to hash
+///
+/// # Examples
+///
+/// ```no_run
+/// use bad_sha1::hash_file_mmap;
+///
+/// let digest = hash_file_mmap("Cargo.toml").unwrap();
+/// ```
+///
+#[cfg(feature = "mmap")]
+pub fn hash_file_mmap<P: AsRef<Path>>(path: P) -> io::Result<[u8; 20]> {
+    let mut file = File::open(path)?;
+    let metadata = file.metadata()?;
+    if !metadata.is_file() || metadata.len() == 0 {
+        return hash_reader(&mut file);
+    }
+
+    // SAFETY: the mapping is read-only and dropped before returning. As with
+    // any mmap, the caller must ensure the file is not changed concurrently.
+    let map = unsafe { Mmap::map(&file)? };
+    Ok(hash(&map[..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hash, hash_bufread, hash_file, hash_reader, hash_reader_with_capacity,
+        hash_reader_with_progress, HashingReader, HashingWriter, Sha1,
+    };
+    use hex_literal::hex;
+    use std::fs;
+    use std::io::{self, Read, Write};
+    use std::path::PathBuf;
+
+    /// Returns a path in the temp dir unique to this test process, so
+    /// concurrent test runs do not trample each other's files.
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("bad_sha1_test_{}_{}", name, std::process::id()))
+    }
+
+    /// Reader that hands out a few bytes at a time and is interrupted every
+    /// other call.
+    struct TrickleReader<'a> {
+        data: &'a [u8],
+        calls: usize,
+    }
+
+    impl Read for TrickleReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.calls += 1;
+            if self.calls.is_multiple_of(2) {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+
+            let n = (self.calls % 5 + 1).min(self.data.len()).min(buf.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    /// Writer that accepts at most three bytes per call.
+    struct ShortWriter(Vec<u8>);
+
+    impl Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = buf.len().min(3);
+            self.0.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Reader whose every read fails.
+    struct BrokenReader;
+
+    impl Read for BrokenReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    #[test]
+    fn test_write() {
+        let animal = "cog";
+
+        let mut sha1 = Sha1::new();
+        write!(sha1, "The quick brown fox jumps over the lazy {}", animal).unwrap();
+        sha1.flush().unwrap();
+        assert_eq!(
+            sha1.finalize(),
+            hash(b"The quick brown fox jumps over the lazy cog")
+        );
+    }
+
+    #[test]
+    fn test_copy() {
+        let input = [0x5au8; 1000];
+
+        let mut sha1 = Sha1::new();
+        let copied = io::copy(&mut &input[..], &mut sha1).unwrap();
+        assert_eq!(copied, 1000);
+        assert_eq!(sha1.finalize(), hash(input));
+    }
+
+    #[test]
+    fn test_hash_reader() {
+        let input = [0x3cu8; 300];
+
+        let mut reader = TrickleReader {
+            data: &input,
+            calls: 0,
+        };
+        assert_eq!(hash_reader(&mut reader).unwrap(), hash(input));
+        assert_eq!(hash_reader(&mut io::empty()).unwrap(), hash(b""));
+    }
+
+    #[test]
+    fn test_hash_reader_with_progress() {
+        let input = [0x3cu8; 300];
+        let mut reader = TrickleReader {
+            data: &input,
+            calls: 0,
+        };
+        let mut counts = Vec::new();
+
+        let digest = hash_reader_with_progress(&mut reader, |n| counts.push(n)).unwrap();
+        assert_eq!(digest, hash(input));
+        assert!(counts.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(counts.last(), Some(&300));
+
+        let mut calls = 0;
+        hash_reader_with_progress(&mut io::empty(), |_| calls += 1).unwrap();
+        assert_eq!(calls, 0);
+    }
+
+    // On 32-bit targets this stream is longer than any slice can be, so only
+    // the u64 byte counters in the read loop and in `Sha1` can get its length
+    // field right.
+    #[test]
+    #[ignore = "hashes over 4 GiB of data"]
+    fn test_hash_reader_past_4gib() {
+        let len = (1u64 << 32) + 100;
+        let mut reader = io::repeat(b'a').take(len);
+        let mut total = 0;
+
+        let digest = hash_reader_with_progress(&mut reader, |n| total = n).unwrap();
+        assert_eq!(total, len);
+        assert_eq!(digest, hex!("2aa5260d55d3fe04e31f93d6d6c47d6973f78be6"));
+    }
+
+    #[test]
+    fn test_hashing_reader() {
+        let input = [0x3cu8; 300];
+        let mut reader = HashingReader::new(TrickleReader {
+            data: &input,
+            calls: 0,
+        });
+
+        let mut copied = Vec::new();
+        reader.read_to_end(&mut copied).unwrap();
+        assert_eq!(copied, input);
+        assert!(reader.get_ref().data.is_empty());
+        assert_eq!(reader.into_digest(), hash(input));
+    }
+
+    #[test]
+    fn test_hashing_writer() {
+        let message = b"The quick brown fox jumps over the lazy dog";
+
+        let mut writer = HashingWriter::new(ShortWriter(Vec::new()));
+        assert_eq!(writer.write(message).unwrap(), 3);
+        writer.write_all(&message[3..]).unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(writer.get_ref().0, &message[..]);
+        assert_eq!(writer.into_digest(), hash(message));
+    }
+
+    #[test]
+    fn test_hashing_writer_file() {
+        let input: Vec<u8> = (0..100_000u32).map(|i| (i * 3) as u8).collect();
+        let path = temp_path("hashing_writer");
+
+        let mut writer = HashingWriter::new(fs::File::create(&path).unwrap());
+        io::copy(&mut &input[..], &mut writer).unwrap();
+        let digest = writer.into_digest();
+
+        let written = fs::read(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(written.unwrap(), input);
+        assert_eq!(digest, hash(&input));
+    }
+
+    #[test]
+    fn test_hash_reader_with_capacity() {
+        let input: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+
+        for &size in [1, 63, 64, 4096, 1 << 20].iter() {
+            let digest = hash_reader_with_capacity(&mut &input[..], size).unwrap();
+            assert_eq!(digest, hash(&input));
+        }
+
+        let err = hash_reader_with_capacity(&mut &input[..], 0).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_hash_bufread() {
+        let input: Vec<u8> = (0..10_000u32).map(|i| (i * 5) as u8).collect();
+
+        for &capacity in [1, 64, 1000, 1 << 16].iter() {
+            let mut reader = io::BufReader::with_capacity(capacity, &input[..]);
+            assert_eq!(hash_bufread(&mut reader).unwrap(), hash(&input));
+        }
+
+        let mut reader = io::BufReader::new(TrickleReader {
+            data: &input,
+            calls: 0,
+        });
+        assert_eq!(hash_bufread(&mut reader).unwrap(), hash(&input));
+        assert_eq!(hash_bufread(&mut io::empty()).unwrap(), hash(b""));
+
+        let err = hash_bufread(&mut io::BufReader::new(BrokenReader)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn test_hash_reader_error() {
+        let mut reader = b"partial".chain(BrokenReader);
+        let err = hash_reader(&mut reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn test_hash_file() {
+        let input: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        let path = temp_path("hash_file");
+        fs::write(&path, &input).unwrap();
+
+        let digest = hash_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(digest.unwrap(), hash(&input));
+    }
+
+    #[test]
+    fn test_hash_file_missing() {
+        let err = hash_file("this/file/does/not/exist").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_hash_file_mmap() {
+        use crate::hash_file_mmap;
+
+        let input: Vec<u8> = (0..200_000u32).map(|i| (i * 7) as u8).collect();
+        let path = temp_path("hash_file_mmap");
+        let empty = temp_path("hash_file_mmap_empty");
+        fs::write(&path, &input).unwrap();
+        fs::write(&empty, b"").unwrap();
+
+        let digest = hash_file_mmap(&path);
+        let empty_digest = hash_file_mmap(&empty);
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&empty).unwrap();
+        assert_eq!(digest.unwrap(), hash(&input));
+        assert_eq!(empty_digest.unwrap(), hash(b""));
+
+        let err = hash_file_mmap("this/file/does/not/exist").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[cfg(all(feature = "mmap", target_os = "linux"))]
+    #[test]
+    fn test_hash_file_mmap_procfs() {
+        use crate::hash_file_mmap;
+
+        // procfs reports a size of 0 for regular files that have content.
+        let path = "/proc/self/cmdline";
+        assert_eq!(fs::metadata(path).unwrap().len(), 0);
+        assert_ne!(hash_file_mmap(path).unwrap(), hash(b""));
+        assert_eq!(hash_file_mmap(path).unwrap(), hash_file(path).unwrap());
+    }
+}
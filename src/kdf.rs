@@ -0,0 +1,260 @@
+use crate::{hmac, HmacSha1, Sha1Error};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+/// Derives key material from a password with PBKDF2-HMAC-SHA1 (RFC 2898
+/// section 5.2), filling all of `out`.
+///
+/// Any output length is supported; as many 20 byte blocks as needed are
+/// computed and the last one is truncated.
+///
+/// # Arguments
+///
+/// *  `password` - Byte slice holding the password
+/// *  `salt` - Byte slice holding the salt
+/// *  `iterations` - Iteration count, which must be at least 1
+/// *  `out` - Buffer the derived key is written to
+///
+/// # Panics
+///
+/// Panics if `iterations` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::pbkdf2;
+/// use hex_literal::hex;
+///
+/// let mut key = [0u8; 20];
+/// pbkdf2(b"password", b"salt", 2, &mut key);
+///
+/// assert_eq!(key, hex!("ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957"));
+/// ```
+///
+pub fn pbkdf2(password: &[u8], salt: &[u8], iterations: u32, out: &mut [u8]) {
+    assert!(iterations > 0, "PBKDF2 requires at least one iteration");
+
+    let prf = HmacSha1::new(password);
+
+    for (i, chunk) in out.chunks_mut(20).enumerate() {
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&(i as u32 + 1).to_be_bytes());
+        let mut u = mac.finalize();
+        let mut block = u;
+
+        for _ in 1..iterations {
+            let mut mac = prf.clone();
+            mac.update(&u);
+            u = mac.finalize();
+
+            for (b, x) in block.iter_mut().zip(u.iter()) {
+                *b ^= x;
+            }
+        }
+
+        chunk.copy_from_slice(&block[..chunk.len()]);
+
+        #[cfg(feature = "zeroize")]
+        {
+            u.zeroize();
+            block.zeroize();
+        }
+    }
+}
+
+/// Maximum number of bytes [`hkdf_expand`] can produce, 255 blocks of 20.
+pub const HKDF_MAX_OUTPUT: usize = 255 * 20;
+
+/// Performs the HKDF-SHA1 (RFC 5869) extract step, condensing the input
+/// keying material `ikm` into a 20 byte pseudorandom key.
+///
+/// An empty `salt` behaves like the 20 zero bytes the RFC uses when no salt
+/// is provided.
+///
+/// # Arguments
+///
+/// *  `salt` - Byte slice holding the optional salt
+/// *  `ikm` - Byte slice holding the input keying material
+///
+pub fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> [u8; 20] {
+    hmac(salt, ikm)
+}
+
+/// Performs the HKDF-SHA1 (RFC 5869) expand step, filling all of `out` with
+/// output keying material derived from `prk` and `info`.
+///
+/// Returns [`Sha1Error::OutputTooLong`] without writing anything if `out`
+/// is longer than [`HKDF_MAX_OUTPUT`] bytes.
+///
+/// # Arguments
+///
+/// *  `prk` - Pseudorandom key, usually from [`hkdf_extract`]
+/// *  `info` - Byte slice holding the context and application specific info
+/// *  `out` - Buffer the output keying material is written to
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hkdf_expand, hkdf_extract};
+///
+/// let prk = hkdf_extract(b"salt", b"input keying material");
+///
+/// let mut okm = [0u8; 42];
+/// hkdf_expand(&prk, b"context", &mut okm).unwrap();
+/// ```
+///
+pub fn hkdf_expand(prk: &[u8; 20], info: &[u8], out: &mut [u8]) -> Result<(), Sha1Error> {
+    if out.len() > HKDF_MAX_OUTPUT {
+        return Err(Sha1Error::OutputTooLong);
+    }
+
+    let prf = HmacSha1::new(prk);
+    let mut t = [0u8; 20];
+
+    for (i, chunk) in out.chunks_mut(20).enumerate() {
+        let mut mac = prf.clone();
+        if i > 0 {
+            mac.update(&t);
+        }
+        mac.update(info);
+        mac.update(&[i as u8 + 1]);
+        t = mac.finalize();
+
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+
+    #[cfg(feature = "zeroize")]
+    t.zeroize();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hkdf_expand, hkdf_extract, pbkdf2, Sha1Error, HKDF_MAX_OUTPUT};
+    use hex_literal::hex;
+
+    // Test vectors from RFC 6070.
+    #[test]
+    fn test_rfc6070() {
+        let mut out = [0u8; 20];
+
+        pbkdf2(b"password", b"salt", 1, &mut out);
+        assert_eq!(out, hex!("0c60c80f961f0e71f3a9b524af6012062fe037a6"));
+
+        pbkdf2(b"password", b"salt", 2, &mut out);
+        assert_eq!(out, hex!("ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957"));
+
+        pbkdf2(b"password", b"salt", 4096, &mut out);
+        assert_eq!(out, hex!("4b007901b765489abead49d926f721d065a429c1"));
+    }
+
+    #[test]
+    fn test_rfc6070_lengths() {
+        let mut out = [0u8; 25];
+        pbkdf2(
+            b"passwordPASSWORDpassword",
+            b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+            4096,
+            &mut out,
+        );
+        assert_eq!(
+            out,
+            hex!("3d2eec4fe41c849b80c8d83662c0e44a8b291a964cf2f07038")
+        );
+
+        let mut out = [0u8; 16];
+        pbkdf2(b"pass\0word", b"sa\0lt", 4096, &mut out);
+        assert_eq!(out, hex!("56fa6aa75548099dcc37d7f03425e0c3"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_iterations() {
+        pbkdf2(b"password", b"salt", 0, &mut [0u8; 20]);
+    }
+
+    // (ikm, salt, info, prk, okm)
+    type TestCase<'a> = (&'a [u8], &'a [u8], &'a [u8], [u8; 20], &'a [u8]);
+
+    // Test cases 4 to 7 from RFC 5869 appendix A.
+    #[test]
+    fn test_rfc5869() {
+        let cases: [TestCase; 4] = [
+            (
+                &[0x0b; 11],
+                &hex!("000102030405060708090a0b0c"),
+                &hex!("f0f1f2f3f4f5f6f7f8f9"),
+                hex!("9b6c18c432a7bf8f0e71c8eb88f4b30baa2ba243"),
+                &hex!(
+                    "085a01ea1b10f36933068b56efa5ad81a4f14b822f5b091568a9cdd4f155fda2"
+                    "c22e422478d305f3f896"
+                ),
+            ),
+            (
+                &hex!(
+                    "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+                    "202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f"
+                    "404142434445464748494a4b4c4d4e4f"
+                ),
+                &hex!(
+                    "606162636465666768696a6b6c6d6e6f707172737475767778797a7b7c7d7e7f"
+                    "808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f"
+                    "a0a1a2a3a4a5a6a7a8a9aaabacadaeaf"
+                ),
+                &hex!(
+                    "b0b1b2b3b4b5b6b7b8b9babbbcbdbebfc0c1c2c3c4c5c6c7c8c9cacbcccdcecf"
+                    "d0d1d2d3d4d5d6d7d8d9dadbdcdddedfe0e1e2e3e4e5e6e7e8e9eaebecedeeef"
+                    "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff"
+                ),
+                hex!("8adae09a2a307059478d309b26c4115a224cfaf6"),
+                &hex!(
+                    "0bd770a74d1160f7c9f12cd5912a06ebff6adcae899d92191fe4305673ba2ffe"
+                    "8fa3f1a4e5ad79f3f334b3b202b2173c486ea37ce3d397ed034c7f9dfeb15c5e"
+                    "927336d0441f4c4300e2cff0d0900b52d3b4"
+                ),
+            ),
+            (
+                &[0x0b; 22],
+                b"",
+                b"",
+                hex!("da8c8a73c7fa77288ec6f5e7c297786aa0d32d01"),
+                &hex!(
+                    "0ac1af7002b3d761d1e55298da9d0506b9ae52057220a306e07b6b87e8df21d0"
+                    "ea00033de03984d34918"
+                ),
+            ),
+            (
+                &[0x0c; 22],
+                &[0x00; 20],
+                b"",
+                hex!("2adccada18779e7c2077ad2eb19d3f3e731385dd"),
+                &hex!(
+                    "2c91117204d745f3500d636a62f64f0ab3bae548aa53d423b0d1f27ebba6f5e5"
+                    "673a081d70cce7acfc48"
+                ),
+            ),
+        ];
+
+        for (ikm, salt, info, prk, okm) in cases.iter() {
+            assert_eq!(hkdf_extract(salt, ikm), *prk);
+
+            let mut out = [0u8; 82];
+            let out = &mut out[..okm.len()];
+            hkdf_expand(prk, info, out).unwrap();
+            assert_eq!(out, *okm);
+        }
+    }
+
+    #[test]
+    fn test_hkdf_output_limit() {
+        let prk = hkdf_extract(b"", b"ikm");
+
+        let mut out = [0u8; HKDF_MAX_OUTPUT + 1];
+        assert_eq!(
+            hkdf_expand(&prk, b"", &mut out),
+            Err(Sha1Error::OutputTooLong)
+        );
+        assert_eq!(hkdf_expand(&prk, b"", &mut out[..HKDF_MAX_OUTPUT]), Ok(()));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::Sha1;
+use alloc::vec::Vec;
+
+/// Performs a length extension attack against a SHA-1 digest.
+///
+/// Given the digest of some unknown message and that message's length,
+/// computes the digest of `message || glue || suffix` without knowing
+/// `message`, where `glue` is the padding SHA-1 appended to the original
+/// message. This is why `hash(secret || message)` is not a secure MAC; use
+/// [`hmac`](crate::hmac) instead.
+///
+/// Returns the forged digest together with the glue padding bytes.
+///
+/// # Arguments
+///
+/// *  `original_digest` - Digest of the unknown original message
+/// *  `original_len` - Length in bytes of the original message
+/// *  `suffix` - Byte slice holding the data to append
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{extend, hash};
+///
+/// let secret = b"hunter2";
+/// let mac = hash(&[&secret[..], b"amount=10"].concat());
+///
+/// let (forged, glue) = extend(&mac, secret.len() + 9, b"&amount=1000");
+///
+/// let message = [&secret[..], b"amount=10", &glue, b"&amount=1000"].concat();
+/// assert_eq!(forged, hash(&message));
+/// ```
+///
+pub fn extend(
+    original_digest: &[u8; 20],
+    original_len: usize,
+    suffix: &[u8],
+) -> ([u8; 20], Vec<u8>) {
+    let glue = glue_padding(original_len as u64);
+
+    let mut state = [0u32; 5];
+    for (word, bytes) in state.iter_mut().zip(original_digest.chunks_exact(4)) {
+        *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+
+    let mut sha1 = Sha1::from_state(state, original_len as u64 + glue.len() as u64);
+    sha1.update(suffix);
+
+    (sha1.finalize(), glue)
+}
+
+/// Builds the padding SHA-1 appends to a message of `len` bytes: a `0x80`
+/// byte, zeros up to 56 bytes into a block, then the bit length.
+fn glue_padding(len: u64) -> Vec<u8> {
+    let zeros = (119 - len % 64) % 64;
+
+    let mut glue = Vec::with_capacity(zeros as usize + 9);
+    glue.push(0x80);
+    glue.resize(zeros as usize + 1, 0);
+    glue.extend_from_slice(&len.wrapping_mul(8).to_be_bytes());
+    glue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glue_padding;
+    use crate::{extend, hash};
+    use alloc::vec::Vec;
+
+    /// MAC construction that is vulnerable to length extension.
+    fn secret_prefix_mac(secret: &[u8], message: &[u8]) -> [u8; 20] {
+        hash([secret, message].concat())
+    }
+
+    #[test]
+    fn test_glue_padding() {
+        for len in 0..200 {
+            let glue = glue_padding(len);
+            assert_eq!((len as usize + glue.len()) % 64, 0);
+            assert!(glue.len() >= 9 && glue.len() <= 72);
+            assert_eq!(glue[0], 0x80);
+            assert_eq!(glue[glue.len() - 8..], (len * 8).to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn test_forge_secret_prefix_mac() {
+        let secret = b"correct horse battery staple";
+        let message = b"user=guest&role=user";
+        let mac = secret_prefix_mac(secret, message);
+
+        // The attacker knows the message, the MAC and the secret's length.
+        let (forged, glue) = extend(&mac, secret.len() + message.len(), b"&role=admin");
+
+        let mut forged_message: Vec<u8> = message.to_vec();
+        forged_message.extend_from_slice(&glue);
+        forged_message.extend_from_slice(b"&role=admin");
+        assert_eq!(secret_prefix_mac(secret, &forged_message), forged);
+    }
+}
@@ -1,13 +1,107 @@
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "portable_simd", feature(portable_simd))]
 
-const HASH_CONSTANTS: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(all(feature = "armv8_crypto", target_arch = "aarch64"))]
+mod aarch64;
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "rayon")]
+mod batch;
+mod collision;
+mod const_hash;
+pub mod consts;
+mod digest;
+#[cfg(feature = "digest")]
+mod digest_impls;
+mod domain;
+mod encoding;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod git;
+mod hasher;
+mod hmac;
+#[cfg(feature = "std")]
+mod io;
+mod kdf;
+#[cfg(feature = "alloc")]
+mod length_extension;
+mod multi;
+mod otp;
+mod prefix;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "portable_simd")]
+mod simd;
+#[cfg(feature = "trace")]
+mod trace;
+mod uuid;
+mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(all(feature = "sha_ni", target_arch = "x86_64"))]
+mod x86;
+#[cfg(feature = "zeroize")]
+mod zeroizing;
+
+#[cfg(feature = "tokio")]
+pub use async_io::hash_async;
+#[cfg(feature = "rayon")]
+pub use batch::{hash_batch, tree_hash, verify_batch};
+pub use collision::{hash_checked, hash_with_collision_detection, is_known_collision_prefix};
+pub use const_hash::hash_const;
+pub use consts::{BLOCK_SIZE, OUTPUT_SIZE};
+pub use digest::{hash_digest, Digest};
+pub use domain::{hash_domain, hash_salted};
+pub use encoding::hash_hex_bytes;
+#[cfg(feature = "alloc")]
+pub use encoding::{base32_encode, hash_hex};
+pub use error::Sha1Error;
+pub use git::{git_blob_oid, git_object_oid, GitObjectKind};
+pub use hasher::Sha1Hasher;
+#[cfg(feature = "alloc")]
+pub use hmac::hmac_hex;
+pub use hmac::{hmac, HmacSha1, HmacSha1Key};
+#[cfg(feature = "mmap")]
+pub use io::hash_file_mmap;
+#[cfg(feature = "std")]
+pub use io::{
+    hash_bufread, hash_file, hash_reader, hash_reader_with_capacity, hash_reader_with_progress,
+    HashingReader, HashingWriter,
+};
+pub use kdf::{hkdf_expand, hkdf_extract, pbkdf2, HKDF_MAX_OUTPUT};
+#[cfg(feature = "alloc")]
+pub use length_extension::extend;
+pub use multi::hash4;
+pub use otp::{hotp, totp, totp_verify};
+pub use prefix::Sha1Prefix;
+#[cfg(feature = "trace")]
+pub use trace::compress_traced;
+pub use uuid::{
+    uuid_v5, UUID_NAMESPACE_DNS, UUID_NAMESPACE_OID, UUID_NAMESPACE_URL, UUID_NAMESPACE_X500,
+};
+pub use verify::{verify, verify_hex};
+#[cfg(feature = "zeroize")]
+pub use zeroizing::ZeroizingSha1;
+
+use consts::HASH_CONSTANTS;
+use core::convert::TryInto;
+use core::fmt;
+#[cfg(feature = "portable_simd")]
+use simd::update_hash as update_hash_soft;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 /// Hashes the given input using the SHA-1 (Secure Hash Algorithm 1)
 /// cryptographic hash function, returning the 5 word digest.
 ///
 /// # Arguments
 ///
-/// *  `input` - Byte slice holding input message
+/// *  `input` - Input message, anything that can be viewed as bytes such as
+///    `&[u8]`, `&str`, `String` or `Vec<u8>`
 ///
 /// # Examples
 ///
@@ -19,55 +113,975 @@ const HASH_CONSTANTS: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476
 ///     hash(b"The quick brown fox jumps over the lazy dog"),
 ///     hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"),
 /// );
+/// assert_eq!(
+///     hash("The quick brown fox jumps over the lazy dog"),
+///     hash(String::from("The quick brown fox jumps over the lazy dog")),
+/// );
+/// ```
+///
+pub fn hash<T: AsRef<[u8]>>(input: T) -> [u8; 20] {
+    let mut sha1 = Sha1::new();
+    sha1.update(input.as_ref());
+    sha1.finalize()
+}
+
+/// Hashes the UTF-8 bytes of a string using SHA-1.
+///
+/// This is the same as `hash(s.as_bytes())`, spelled out for readability.
+///
+/// # Arguments
+///
+/// *  `s` - String slice holding input message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_str};
+///
+/// assert_eq!(hash_str("héllo"), hash("héllo".as_bytes()));
+/// ```
+///
+pub fn hash_str(s: &str) -> [u8; 20] {
+    hash(s.as_bytes())
+}
+
+/// Hashes the given input using SHA-1, returning only the first `N` bytes
+/// of the digest. Using an `N` above 20 fails to compile.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::hash_truncated;
+/// use hex_literal::hex;
+///
+/// let fingerprint: [u8; 8] = hash_truncated(b"abc");
+///
+/// assert_eq!(fingerprint, hex!("a9993e364706816a"));
+/// ```
+///
+/// ```compile_fail
+/// let too_long: [u8; 21] = bad_sha1::hash_truncated(b"abc");
+/// ```
+///
+pub fn hash_truncated<const N: usize>(input: &[u8]) -> [u8; N] {
+    const { assert!(N <= 20, "SHA-1 digests are only 20 bytes long") };
+
+    let mut out = [0u8; N];
+    out.copy_from_slice(&hash(input)[..N]);
+    out
+}
+
+/// Longest message, in bytes, that SHA-1 is defined for: the length field
+/// holds the message length in bits and must be below 2^64, so messages are
+/// limited to 2^61 - 1 bytes.
+pub const MAX_INPUT_LEN: u64 = (1 << 61) - 1;
+
+/// Hashes the given input using SHA-1, failing instead of wrapping the
+/// length field if the input is longer than SHA-1 allows.
+///
+/// Inputs of up to [`MAX_INPUT_LEN`] bytes (2^61 - 1) are hashed exactly
+/// like [`hash`]; anything longer returns [`Sha1Error::InputTooLong`]. That
+/// limit can only be reached on 64-bit targets.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, try_hash};
+///
+/// assert_eq!(try_hash(b"abc"), Ok(hash(b"abc")));
+/// ```
+///
+pub fn try_hash(input: &[u8]) -> Result<[u8; 20], Sha1Error> {
+    if input.len() as u64 > MAX_INPUT_LEN {
+        return Err(Sha1Error::InputTooLong);
+    }
+    Ok(hash(input))
+}
+
+/// Hashes the bytes produced by an iterator using SHA-1, without collecting
+/// them first. The length does not need to be known in advance.
+///
+/// # Arguments
+///
+/// *  `iter` - Iterator yielding the input message one byte at a time
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_iter};
+///
+/// let digest = hash_iter((0..1000u32).map(|i| (i % 256) as u8));
+///
+/// assert_eq!(digest, hash(&(0..1000u32).map(|i| (i % 256) as u8).collect::<Vec<_>>()));
+/// ```
+///
+pub fn hash_iter<I: IntoIterator<Item = u8>>(iter: I) -> [u8; 20] {
+    let mut sha1 = Sha1::new();
+    sha1.extend(iter);
+    sha1.finalize()
+}
+
+/// Hashes the concatenation of the slices produced by an iterator using
+/// SHA-1, the streaming counterpart of [`hash_vectored`]. Chunks may be any
+/// length and need not line up with block boundaries.
+///
+/// # Arguments
+///
+/// *  `chunks` - Iterator yielding consecutive parts of the message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_chunks};
+///
+/// let framed = b"3:abc5:hello";
+/// let payloads = [&framed[2..5], &framed[7..]];
+///
+/// assert_eq!(hash_chunks(payloads.iter().copied()), hash(b"abchello"));
+/// ```
+///
+pub fn hash_chunks<'a, I: IntoIterator<Item = &'a [u8]>>(chunks: I) -> [u8; 20] {
+    let mut sha1 = Sha1::new();
+    sha1.extend(chunks);
+    sha1.finalize()
+}
+
+/// Hashes the first `bit_len` bits of `input` using SHA-1, for messages
+/// whose length is not a whole number of bytes.
+///
+/// Bits are taken most significant first, so a trailing partial byte uses
+/// its high bits and the rest are ignored. The length field records
+/// `bit_len` itself.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+/// *  `bit_len` - Number of leading bits of `input` that form the message
+///
+/// # Panics
+///
+/// Panics if `bit_len` is greater than `input.len() * 8`.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_bits};
+/// use hex_literal::hex;
+///
+/// // The five bit message 10011 (RFC 6234).
+/// assert_eq!(
+///     hash_bits(&[0x98], 5),
+///     hex!("29826b003b906e660eff4027ce98af3531ac75ba"),
+/// );
+/// assert_eq!(hash_bits(b"abc", 24), hash(b"abc"));
+/// ```
+///
+pub fn hash_bits(input: &[u8], bit_len: u64) -> [u8; 20] {
+    assert!(
+        bit_len <= (input.len() as u64).saturating_mul(8),
+        "bit_len is longer than the input"
+    );
+
+    let full = (bit_len / 8) as usize;
+    let extra = (bit_len % 8) as u32;
+
+    let mut sha1 = Sha1::new();
+    sha1.update(&input[..full]);
+
+    // The partial byte fits in the position the byte padding puts 0x80, so
+    // only the terminator byte and the length field need adjusting.
+    let mut blocks = [[0u8; 64]; 2];
+    let tail = &sha1.block[..sha1.block_len];
+    let count = padding_blocks(tail, 0, &mut blocks);
+
+    if extra > 0 {
+        let bits = input[full] & !(0xff >> extra);
+        blocks[0][tail.len()] = bits | (0x80 >> extra);
+    }
+    blocks[count - 1][56..].copy_from_slice(&bit_len.to_be_bytes());
+
+    for block in blocks[..count].iter() {
+        compress(&mut sha1.state, block);
+    }
+
+    #[cfg(feature = "zeroize")]
+    blocks.zeroize();
+
+    let mut output = [0u8; 20];
+    write_digest(&sha1.state, &mut output);
+    output
+}
+
+/// Hashes a message split across several buffers using SHA-1, giving the
+/// same digest as hashing their concatenation without building it.
+///
+/// # Arguments
+///
+/// *  `slices` - Byte slices holding consecutive parts of the message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, hash_vectored};
+///
+/// let digest = hash_vectored(&[b"header:", b"body", b";trailer"]);
+///
+/// assert_eq!(digest, hash(b"header:body;trailer"));
+/// ```
+///
+pub fn hash_vectored(slices: &[&[u8]]) -> [u8; 20] {
+    let mut sha1 = Sha1::new();
+    sha1.extend(slices.iter().copied());
+    sha1.finalize()
+}
+
+/// Hashes the concatenation of any number of `AsRef<[u8]>` arguments using
+/// SHA-1, feeding each one to a streaming [`Sha1`] in order.
+///
+/// This is shorthand for [`hash_vectored`] when the parts have different
+/// types or are named individually at the call site.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, sha1};
+///
+/// let prefix = "header:";
+/// let body = vec![b'b', b'o', b'd', b'y'];
+///
+/// assert_eq!(sha1!(prefix, body, b";trailer"), hash(b"header:body;trailer"));
+/// assert_eq!(sha1!(), hash(b""));
+/// ```
+///
+#[macro_export]
+macro_rules! sha1 {
+    ($($part:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut sha1 = $crate::Sha1::new();
+        $(sha1.update(::core::convert::AsRef::<[u8]>::as_ref(&$part));)*
+        sha1.finalize()
+    }};
+}
+
+/// Hashes the given input using SHA-1, returning the digest as five words
+/// rather than 20 bytes.
+///
+/// Word `i` holds digest bytes `4 * i..4 * i + 4` in big-endian order.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::hash_words;
+///
+/// assert_eq!(
+///     hash_words(b"The quick brown fox jumps over the lazy dog"),
+///     [0x2fd4e1c6, 0x7a2d28fc, 0xed849ee1, 0xbb76e739, 0x1b93eb12],
+/// );
+/// ```
+///
+pub fn hash_words(input: &[u8]) -> [u32; 5] {
+    let mut sha1 = Sha1::new();
+    sha1.update(input);
+    sha1.finalize_words()
+}
+
+/// Runs the compression function over `blocks` from the standard initial
+/// state and returns the raw chaining state, without any padding or length
+/// field.
+///
+/// This is only equal to a SHA-1 digest when the caller has already padded
+/// the message, and is otherwise a building block for custom padding
+/// schemes and midstate computations.
+///
+/// # Arguments
+///
+/// *  `blocks` - Byte slice holding whole 64 byte blocks
+///
+/// # Panics
+///
+/// Panics if the length of `blocks` is not a multiple of 64.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash_blocks, hash_words};
+///
+/// // "abc" padded by hand into a single block
+/// let mut block = [0u8; 64];
+/// block[..3].copy_from_slice(b"abc");
+/// block[3] = 0x80;
+/// block[63] = 24;
+///
+/// assert_eq!(hash_blocks(&block), hash_words(b"abc"));
+/// ```
+///
+pub fn hash_blocks(blocks: &[u8]) -> [u32; 5] {
+    assert!(
+        blocks.len().is_multiple_of(64),
+        "hash_blocks input must be a whole number of 64 byte blocks"
+    );
+
+    let mut state = HASH_CONSTANTS;
+    for block in blocks.chunks_exact(64) {
+        compress(&mut state, block.try_into().unwrap());
+    }
+    state
+}
+
+/// Streaming SHA-1 hasher, for input that is not available all at once.
+///
+/// Data is fed in with [`Sha1::update`] and the digest is produced by
+/// [`Sha1::finalize`]. Feeding a message in any number of pieces gives the
+/// same digest as passing the whole message to [`hash`].
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::Sha1;
+/// use hex_literal::hex;
+///
+/// let mut sha1 = Sha1::new();
+/// sha1.update(b"The quick brown fox ");
+/// sha1.update(b"jumps over the lazy dog");
+///
+/// assert_eq!(
+///     sha1.finalize(),
+///     hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"),
+/// );
+/// ```
+///
+/// Cloning a hasher snapshots everything fed so far, so a shared prefix can
+/// be hashed once and then continued with different suffixes.
+///
+/// The hasher never allocates: the partial block is kept in an inline
+/// `[u8; 64]` buffer, so the whole value is 104 bytes on 64-bit targets and
+/// 96 bytes on 32-bit ones and can live on the stack or in a `static`. It is
+/// also `Copy`.
+///
+/// Being `Copy`, the hasher is not wiped when dropped. With the `zeroize`
+/// feature enabled it implements `Zeroize`, the `finalize` methods wipe the
+/// copy they consume, and `ZeroizingSha1` wraps it to overwrite the state
+/// and any buffered input with zeros on drop.
+#[derive(Clone, Copy)]
+pub struct Sha1 {
+    state: [u32; 5],
+    block: [u8; 64],
+    block_len: usize,
+    /// Total message bytes fed so far. This is a `u64` rather than a `usize`
+    /// so that streams over 4 GiB are counted correctly on 32-bit targets.
+    length: u64,
+}
+
+impl Sha1 {
+    /// The size in bytes of the blocks the hasher processes.
+    pub const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    /// The size in bytes of the digest the hasher produces.
+    pub const OUTPUT_SIZE: usize = OUTPUT_SIZE;
+
+    /// Creates a new hasher with the standard SHA-1 initial state.
+    pub fn new() -> Self {
+        Sha1::with_iv(HASH_CONSTANTS)
+    }
+
+    /// Creates a new hasher starting from a custom five word initialization
+    /// vector instead of the standard one.
+    ///
+    /// Padding and finalization are unchanged, and the length field only
+    /// counts bytes fed to this hasher. To continue a message whose earlier
+    /// blocks are already folded into the state, use
+    /// [`Sha1::from_midstate`] instead.
+    ///
+    /// # Arguments
+    ///
+    /// *  `iv` - The initial hash state
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::{hash, Sha1};
+    ///
+    /// let iv = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    ///
+    /// let mut sha1 = Sha1::with_iv(iv);
+    /// sha1.update(b"abc");
+    ///
+    /// assert_eq!(sha1.finalize(), hash(b"abc"));
+    /// ```
+    ///
+    pub fn with_iv(iv: [u32; 5]) -> Self {
+        Sha1::from_state(iv, 0)
+    }
+
+    /// Resumes hashing from a state previously exported with
+    /// [`Sha1::midstate`].
+    ///
+    /// Only whole blocks can be resumed from, so `processed` must be a
+    /// multiple of 64, otherwise [`Sha1Error::BadMidstateLength`] is
+    /// returned.
+    ///
+    /// # Arguments
+    ///
+    /// *  `state` - The five word intermediate hash state
+    /// *  `processed` - Number of message bytes the state covers
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::{hash, Sha1};
+    ///
+    /// let prefix = [b'#'; 128];
+    ///
+    /// let mut sha1 = Sha1::new();
+    /// sha1.update(&prefix);
+    /// let (state, processed) = sha1.midstate();
+    ///
+    /// let mut resumed = Sha1::from_midstate(state, processed).unwrap();
+    /// resumed.update(b"suffix");
+    ///
+    /// assert_eq!(resumed.finalize(), hash(&[&prefix[..], b"suffix"].concat()));
+    /// ```
+    ///
+    pub fn from_midstate(state: [u32; 5], processed: u64) -> Result<Self, Sha1Error> {
+        if !processed.is_multiple_of(64) {
+            return Err(Sha1Error::BadMidstateLength);
+        }
+        Ok(Sha1::from_state(state, processed))
+    }
+
+    /// Exports the intermediate hash state along with the number of message
+    /// bytes it covers, which is always a multiple of 64.
+    ///
+    /// Bytes of a trailing partial block are still buffered and are not part
+    /// of the exported state; they must be fed again after resuming with
+    /// [`Sha1::from_midstate`].
+    pub fn midstate(&self) -> ([u32; 5], u64) {
+        (self.state, self.length - self.block_len as u64)
+    }
+
+    /// Returns the raw five word chaining state without padding or
+    /// finalizing anything.
+    ///
+    /// **This is not a SHA-1 digest.** It only reflects the whole blocks
+    /// compressed so far: buffered bytes of a trailing partial block, the
+    /// padding and the length field are all missing from it. It is meant
+    /// for building custom Merkle–Damgård constructions together with
+    /// [`Sha1::with_iv`]; use [`Sha1::finalize`] to get an actual digest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::{compress, Sha1};
+    ///
+    /// let iv = [1, 2, 3, 4, 5];
+    ///
+    /// let mut sha1 = Sha1::with_iv(iv);
+    /// sha1.update(&[0x42; 64]);
+    ///
+    /// let mut expected = iv;
+    /// compress(&mut expected, &[0x42; 64]);
+    /// assert_eq!(sha1.state(), expected);
+    /// ```
+    ///
+    pub fn state(&self) -> [u32; 5] {
+        self.state
+    }
+
+    /// Returns how many 64 byte blocks have been compressed so far. Bytes of
+    /// a trailing partial block are not counted until the block fills up.
+    pub fn blocks_processed(&self) -> u64 {
+        (self.length - self.block_len as u64) / 64
+    }
+
+    /// Returns the total number of bytes fed into the hasher so far,
+    /// including any resumed with [`Sha1::from_midstate`].
+    pub fn bytes_absorbed(&self) -> u64 {
+        self.length
+    }
+
+    /// Creates a hasher that resumes from `state` after `length` bytes have
+    /// already been compressed. `length` must be a multiple of 64.
+    fn from_state(state: [u32; 5], length: u64) -> Self {
+        debug_assert_eq!(length % 64, 0);
+        Sha1 {
+            state,
+            block: [0u8; 64],
+            block_len: 0,
+            length,
+        }
+    }
+
+    /// Feeds more of the message into the hasher.
+    ///
+    /// # Arguments
+    ///
+    /// *  `data` - Byte slice holding the next part of the message
+    ///
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        if self.block_len > 0 {
+            let take = (64 - self.block_len).min(data.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&data[..take]);
+            self.block_len += take;
+            data = &data[take..];
+
+            if self.block_len < 64 {
+                return;
+            }
+
+            compress(&mut self.state, &self.block);
+            self.block_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+
+        for block in blocks.by_ref() {
+            compress(&mut self.state, block.try_into().unwrap());
+        }
+
+        let remainder = blocks.remainder();
+        self.block[..remainder.len()].copy_from_slice(remainder);
+        self.block_len = remainder.len();
+    }
+
+    /// Feeds several consecutive parts of the message into the hasher, the
+    /// same as calling [`Sha1::update`] on each in turn.
+    ///
+    /// # Arguments
+    ///
+    /// *  `slices` - Byte slices holding the next parts of the message
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::{hash, Sha1};
+    ///
+    /// let mut sha1 = Sha1::new();
+    /// sha1.update_many(&[b"header:", b"body", b";trailer"]);
+    ///
+    /// assert_eq!(sha1.finalize(), hash(b"header:body;trailer"));
+    /// ```
+    ///
+    pub fn update_many(&mut self, slices: &[&[u8]]) {
+        for data in slices {
+            self.update(data);
+        }
+    }
+
+    /// Discards everything fed so far, returning the hasher to the state of
+    /// a freshly created one so it can be reused for another message.
+    pub fn reset(&mut self) {
+        *self = Sha1::new();
+    }
+
+    /// Pads the message, processes the final block(s) and returns the
+    /// 20 byte digest.
+    pub fn finalize(self) -> [u8; 20] {
+        let mut output = [0u8; 20];
+        self.finalize_into(&mut output);
+        output
+    }
+
+    /// Pads the message, processes the final block(s) and writes the 20 byte
+    /// digest into `out`.
+    ///
+    /// # Arguments
+    ///
+    /// *  `out` - Buffer the digest is written to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::{hash, Sha1};
+    /// use std::convert::TryInto;
+    ///
+    /// let mut record = [0u8; 32];
+    ///
+    /// let mut sha1 = Sha1::new();
+    /// sha1.update(b"abc");
+    /// sha1.finalize_into((&mut record[12..]).try_into().unwrap());
+    ///
+    /// assert_eq!(record[12..], hash(b"abc"));
+    /// ```
+    ///
+    pub fn finalize_into(mut self, out: &mut [u8; 20]) {
+        self.pad();
+        write_digest(&self.state, out);
+
+        #[cfg(feature = "zeroize")]
+        self.zeroize();
+    }
+
+    /// Pads the message, processes the final block(s) and returns the
+    /// 20 byte digest along with the total number of bytes hashed, the
+    /// same count as [`Sha1::bytes_absorbed`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::{hash, Sha1};
+    ///
+    /// let mut sha1 = Sha1::new();
+    /// sha1.update(b"The quick brown fox ");
+    /// sha1.update(b"jumps over the lazy dog");
+    ///
+    /// let (digest, len) = sha1.finalize_with_len();
+    /// assert_eq!(digest, hash(b"The quick brown fox jumps over the lazy dog"));
+    /// assert_eq!(len, 43);
+    /// ```
+    ///
+    pub fn finalize_with_len(self) -> ([u8; 20], u64) {
+        let length = self.length;
+        (self.finalize(), length)
+    }
+
+    /// Pads the message, processes the final block(s) and returns the
+    /// digest as five words, the big-endian reading of the 20 digest bytes.
+    pub fn finalize_words(mut self) -> [u32; 5] {
+        self.pad();
+        let words = self.state;
+
+        #[cfg(feature = "zeroize")]
+        self.zeroize();
+        words
+    }
+
+    /// Returns the digest of everything fed so far and resets the hasher,
+    /// so the same instance can go on to hash the next message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bad_sha1::{hash, Sha1};
+    ///
+    /// let mut sha1 = Sha1::new();
+    ///
+    /// for record in [&b"first"[..], b"second", b"third"].iter() {
+    ///     sha1.update(record);
+    ///     assert_eq!(sha1.finalize_reset(), hash(record));
+    /// }
+    /// ```
+    ///
+    pub fn finalize_reset(&mut self) -> [u8; 20] {
+        self.pad();
+
+        let mut output = [0u8; 20];
+        write_digest(&self.state, &mut output);
+        self.reset();
+        output
+    }
+
+    /// Appends the padding and length field and processes the final
+    /// block(s), leaving the digest in `self.state`.
+    fn pad(&mut self) {
+        let mut blocks = [[0u8; 64]; 2];
+        let count = padding_blocks(&self.block[..self.block_len], self.length, &mut blocks);
+
+        for block in blocks[..count].iter() {
+            compress(&mut self.state, block);
+        }
+
+        #[cfg(feature = "zeroize")]
+        blocks.zeroize();
+    }
+}
+
+/// Writes the final block(s) of a message into `blocks`: the trailing
+/// partial block `tail`, the `0x80` terminator, zero padding and the length
+/// field for a message of `length` bytes in total. Returns how many of the
+/// two blocks are used. `blocks` must be zeroed beforehand.
+fn padding_blocks(tail: &[u8], length: u64, blocks: &mut [[u8; 64]; 2]) -> usize {
+    let rem_len = tail.len();
+
+    blocks[0][..rem_len].copy_from_slice(tail);
+    blocks[0][rem_len] = 0x80;
+
+    let count = if rem_len > 55 { 2 } else { 1 };
+
+    // SHA-1 defines the length field as the message length in bits
+    // modulo 2^64.
+    let bit_length = length.wrapping_mul(8);
+    blocks[count - 1][56..].copy_from_slice(&bit_length.to_be_bytes());
+    count
+}
+
+/// Same as [`Sha1::new`].
+impl Default for Sha1 {
+    fn default() -> Self {
+        Sha1::new()
+    }
+}
+
+/// Feeds bytes from an iterator into the hasher, as if passed to
+/// [`Sha1::update`].
+impl Extend<u8> for Sha1 {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        // Gather whole blocks locally so update() runs once per block
+        // rather than once per byte.
+        let mut block = [0u8; 64];
+        let mut block_len = 0;
+
+        for byte in iter {
+            block[block_len] = byte;
+            block_len += 1;
+            if block_len == 64 {
+                self.update(&block);
+                block_len = 0;
+            }
+        }
+        self.update(&block[..block_len]);
+
+        #[cfg(feature = "zeroize")]
+        block.zeroize();
+    }
+}
+
+/// Feeds each slice from an iterator into the hasher in order.
+impl<'a> Extend<&'a [u8]> for Sha1 {
+    fn extend<I: IntoIterator<Item = &'a [u8]>>(&mut self, iter: I) {
+        for data in iter {
+            self.update(data);
+        }
+    }
+}
+
+/// Feeds formatted text into the hasher as UTF-8, so `write!` can hash
+/// formatted data without building an intermediate `String`.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, Sha1};
+/// use core::fmt::Write;
+///
+/// let mut sha1 = Sha1::new();
+/// write!(sha1, "{}-{}", "order", 42).unwrap();
+///
+/// assert_eq!(sha1.finalize(), hash(b"order-42"));
+/// ```
+///
+impl fmt::Write for Sha1 {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.update(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Serializes the five digest words into 20 big-endian bytes.
+fn write_digest(hash: &[u32; 5], out: &mut [u8; 20]) {
+    for word in 0..5 {
+        out[word * 4] = (hash[word] >> 24) as u8;
+        out[word * 4 + 1] = (hash[word] >> 16) as u8;
+        out[word * 4 + 2] = (hash[word] >> 8) as u8;
+        out[word * 4 + 3] = hash[word] as u8;
+    }
+}
+
+/// Returns message schedule word `t`, keeping only the last 16 words in `w`
+/// and computing later words in place as they are needed.
+#[cfg(not(feature = "portable_simd"))]
+#[inline(always)]
+fn schedule(w: &mut [u32; 16], t: usize) -> u32 {
+    if t >= 16 {
+        w[t & 15] =
+            (w[(t + 13) & 15] ^ w[(t + 8) & 15] ^ w[(t + 2) & 15] ^ w[t & 15]).rotate_left(1);
+    }
+    w[t & 15]
+}
+
+/// Applies the SHA-1 compression function, folding one 64 byte block into the
+/// five word chaining state.
+///
+/// This is the building block used by every hasher in the crate. It does no
+/// padding or length tracking, so it is only useful for building custom
+/// constructions on top of it. The CPU's SHA-1 instructions are used when
+/// the matching feature is enabled and the CPU supports them.
+///
+/// # Arguments
+///
+/// *  `state` - The chaining state to update in place
+/// *  `block` - The 64 byte block to process
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{compress, hash_words};
+///
+/// // "abc" padded by hand into a single block
+/// let mut block = [0u8; 64];
+/// block[..3].copy_from_slice(b"abc");
+/// block[3] = 0x80;
+/// block[63] = 24;
+///
+/// let mut state = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+/// compress(&mut state, &block);
+///
+/// assert_eq!(state, hash_words(b"abc"));
+/// ```
+///
+pub fn compress(state: &mut [u32; 5], block: &[u8; 64]) {
+    #[cfg(any(
+        all(feature = "sha_ni", target_arch = "x86_64"),
+        all(feature = "armv8_crypto", target_arch = "aarch64"),
+    ))]
+    {
+        match accelerated_compress() {
+            Some(accelerated) => accelerated(state, block),
+            None => update_hash_soft(state, block),
+        }
+    }
+
+    #[cfg(not(any(
+        all(feature = "sha_ni", target_arch = "x86_64"),
+        all(feature = "armv8_crypto", target_arch = "aarch64"),
+    )))]
+    update_hash_soft(state, block)
+}
+
+/// Applies the SHA-1 compression function to each of `blocks` in turn,
+/// the same as calling [`compress`] once per block.
+///
+/// Like [`compress`] this does no padding or length tracking; the caller
+/// decides how the message is framed into blocks.
+///
+/// # Arguments
+///
+/// *  `state` - The chaining state to update in place
+/// *  `blocks` - The 64 byte blocks to process, in order
+///
+/// # Examples
+///
 /// ```
+/// use bad_sha1::{compress_blocks, hash_blocks};
+///
+/// let blocks = [[0x5a; 64]; 3];
 ///
-pub fn hash(input: &[u8]) -> [u8; 20] {
-    let mut hash: [u32; 5] = HASH_CONSTANTS;
+/// let mut state = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+/// compress_blocks(&mut state, &blocks);
+///
+/// assert_eq!(state, hash_blocks(&[0x5a; 192]));
+/// ```
+///
+pub fn compress_blocks(state: &mut [u32; 5], blocks: &[[u8; 64]]) {
+    for block in blocks {
+        compress(state, block);
+    }
+}
+
+#[cfg(any(
+    all(feature = "sha_ni", target_arch = "x86_64"),
+    all(feature = "armv8_crypto", target_arch = "aarch64"),
+))]
+type CompressFn = fn(&mut [u32; 5], &[u8; 64]);
+
+/// Returns whether [`compress`] runs on the CPU's SHA-1 instructions rather
+/// than the portable implementation.
+fn compress_accelerated() -> bool {
+    #[cfg(any(
+        all(feature = "sha_ni", target_arch = "x86_64"),
+        all(feature = "armv8_crypto", target_arch = "aarch64"),
+    ))]
+    {
+        accelerated_compress().is_some()
+    }
+
+    #[cfg(not(any(
+        all(feature = "sha_ni", target_arch = "x86_64"),
+        all(feature = "armv8_crypto", target_arch = "aarch64"),
+    )))]
+    false
+}
 
-    let mut blocks = input.chunks_exact(64);
+/// Returns the hardware compression function, if the CPU supports one and
+/// it passed [`self_test`].
+#[cfg(any(
+    all(feature = "sha_ni", target_arch = "x86_64"),
+    all(feature = "armv8_crypto", target_arch = "aarch64"),
+))]
+fn accelerated_compress() -> Option<CompressFn> {
+    // Feature detection runs once, after which every call goes straight
+    // to the selected implementation.
+    static COMPRESS: std::sync::OnceLock<Option<CompressFn>> = std::sync::OnceLock::new();
+    *COMPRESS.get_or_init(select_compress)
+}
 
-    for block in blocks.by_ref() {
-        update_hash(&mut hash, block);
+/// Picks the fastest compression function the CPU supports, or `None` to
+/// use the portable one.
+#[cfg(any(
+    all(feature = "sha_ni", target_arch = "x86_64"),
+    all(feature = "armv8_crypto", target_arch = "aarch64"),
+))]
+fn select_compress() -> Option<CompressFn> {
+    #[cfg(all(feature = "sha_ni", target_arch = "x86_64"))]
+    {
+        if x86::sha_ni_available() {
+            // SAFETY: only selected once the required CPU features have been
+            // detected.
+            let sha_ni: CompressFn = |state, block| unsafe { x86::update_hash(state, block) };
+            if self_test(sha_ni) {
+                return Some(sha_ni);
+            }
+        }
     }
 
-    let remainder = blocks.remainder();
-    let rem_len = remainder.len();
+    #[cfg(all(feature = "armv8_crypto", target_arch = "aarch64"))]
+    {
+        if aarch64::sha1_available() {
+            // SAFETY: only selected once the required CPU features have been
+            // detected.
+            let armv8: CompressFn = |state, block| unsafe { aarch64::update_hash(state, block) };
+            if self_test(armv8) {
+                return Some(armv8);
+            }
+        }
+    }
 
-    let mut last_block = [0u8; 64];
-    last_block[..rem_len].copy_from_slice(remainder);
-    last_block[rem_len] = 0x80;
+    None
+}
 
-    if rem_len > 54 {
-        update_hash(&mut hash, &last_block);
-        last_block = [0u8; 64];
-    }
+/// Checks a compression function against the "abc" test vector before it
+/// is trusted, so a broken hardware path (say, a byte order mistake on an
+/// unusual target) falls back to the portable code instead of producing
+/// wrong digests. Debug builds panic on a failure so it gets noticed.
+#[cfg(any(
+    all(feature = "sha_ni", target_arch = "x86_64"),
+    all(feature = "armv8_crypto", target_arch = "aarch64"),
+))]
+fn self_test(compress: CompressFn) -> bool {
+    let mut block = [0u8; 64];
+    block[..3].copy_from_slice(b"abc");
+    block[3] = 0x80;
+    block[63] = 24;
 
-    let bit_length = input.len() as u64 * 8;
-    last_block[56..].copy_from_slice(&bit_length.to_be_bytes());
-    update_hash(&mut hash, &last_block);
+    let mut state = HASH_CONSTANTS;
+    compress(&mut state, &block);
 
-    let mut output = [0u8; 20];
-    for word in 0..5 {
-        output[word * 4] = (hash[word] >> 24) as u8;
-        output[word * 4 + 1] = (hash[word] >> 16) as u8;
-        output[word * 4 + 2] = (hash[word] >> 8) as u8;
-        output[word * 4 + 3] = hash[word] as u8;
-    }
-    output
+    let passed = state == [0xa9993e36, 0x4706816a, 0xba3e2571, 0x7850c26c, 0x9cd0d89d];
+    debug_assert!(passed, "hardware SHA-1 compression failed its self-test");
+    passed
 }
 
-fn update_hash(hash: &mut [u32; 5], block: &[u8]) {
-    let mut w = [0u32; 80];
+#[cfg(not(feature = "portable_simd"))]
+fn update_hash_soft(hash: &mut [u32; 5], block: &[u8]) {
+    use consts::ROUND_CONSTANTS;
 
-    for t in 0..16 {
-        w[t] = (block[t * 4] as u32) << 24;
-        w[t] |= (block[t * 4 + 1] as u32) << 16;
-        w[t] |= (block[t * 4 + 2] as u32) << 8;
-        w[t] |= block[t * 4 + 3] as u32;
-    }
+    let mut w = [0u32; 16];
 
-    for t in 16..80 {
-        w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+    for (word, bytes) in w.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
     }
 
     let mut a = hash[0];
@@ -76,13 +1090,14 @@ fn update_hash(hash: &mut [u32; 5], block: &[u8]) {
     let mut d = hash[3];
     let mut e = hash[4];
 
-    for &x in &w[0..20] {
+    for t in 0..20 {
+        let x = schedule(&mut w, t);
         let temp = a
             .rotate_left(5)
             .wrapping_add((b & c) | (!b & d))
             .wrapping_add(e)
             .wrapping_add(x)
-            .wrapping_add(0x5A827999);
+            .wrapping_add(ROUND_CONSTANTS[0]);
 
         e = d;
         d = c;
@@ -91,13 +1106,14 @@ fn update_hash(hash: &mut [u32; 5], block: &[u8]) {
         a = temp;
     }
 
-    for &x in &w[20..40] {
+    for t in 20..40 {
+        let x = schedule(&mut w, t);
         let temp = a
             .rotate_left(5)
             .wrapping_add(b ^ c ^ d)
             .wrapping_add(e)
             .wrapping_add(x)
-            .wrapping_add(0x6ED9EBA1);
+            .wrapping_add(ROUND_CONSTANTS[1]);
 
         e = d;
         d = c;
@@ -106,13 +1122,14 @@ fn update_hash(hash: &mut [u32; 5], block: &[u8]) {
         a = temp;
     }
 
-    for &x in &w[40..60] {
+    for t in 40..60 {
+        let x = schedule(&mut w, t);
         let temp = a
             .rotate_left(5)
             .wrapping_add((b & c) | (b & d) | (c & d))
             .wrapping_add(e)
             .wrapping_add(x)
-            .wrapping_add(0x8F1BBCDC);
+            .wrapping_add(ROUND_CONSTANTS[2]);
 
         e = d;
         d = c;
@@ -121,13 +1138,14 @@ fn update_hash(hash: &mut [u32; 5], block: &[u8]) {
         a = temp;
     }
 
-    for &x in &w[60..80] {
+    for t in 60..80 {
+        let x = schedule(&mut w, t);
         let temp = a
             .rotate_left(5)
             .wrapping_add(b ^ c ^ d)
             .wrapping_add(e)
             .wrapping_add(x)
-            .wrapping_add(0xCA62C1D6);
+            .wrapping_add(ROUND_CONSTANTS[3]);
 
         e = d;
         d = c;
@@ -145,7 +1163,11 @@ fn update_hash(hash: &mut [u32; 5], block: &[u8]) {
 
 #[cfg(test)]
 mod tests {
-    use crate::hash;
+    use crate::{
+        compress, compress_blocks, hash, hash_bits, hash_blocks, hash_chunks, hash_iter, hash_str,
+        hash_truncated, hash_vectored, hash_words, try_hash, Sha1, Sha1Error, HASH_CONSTANTS,
+        MAX_INPUT_LEN,
+    };
     use hex_literal::hex;
 
     #[test]
@@ -176,4 +1198,693 @@ mod tests {
             hex!("7822ad26c30799547bcb3d149ec98ea537eb5761"),
         );
     }
+
+    // Lengths either side of where the length field stops fitting in the
+    // final block, and of where no partial block is left over.
+    #[test]
+    fn test_padding_boundaries() {
+        let input = [b'a'; 120];
+        let expected = [
+            (0, hex!("da39a3ee5e6b4b0d3255bfef95601890afd80709")),
+            (55, hex!("c1c8bbdc22796e28c0e15163d20899b65621d65a")),
+            (56, hex!("c2db330f6083854c99d4b5bfb6e8f29f201be699")),
+            (63, hex!("03f09f5b158a7a8cdad920bddc29b81c18a551f5")),
+            (64, hex!("0098ba824b5c16427bd7a1122a5a442a25ec644d")),
+            (119, hex!("ee971065aaa017e0632a8ca6c77bb3bf8b1dfc56")),
+            (120, hex!("f34c1488385346a55709ba056ddd08280dd4c6d6")),
+        ];
+
+        for (len, digest) in expected.iter() {
+            assert_eq!(hash(&input[..*len]), *digest, "length {}", len);
+            assert_eq!(crate::hash_const(&input[..*len]), *digest, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_empty_updates() {
+        let input = [b'a'; 120];
+
+        for len in [0, 55, 56, 63, 64, 119, 120].iter() {
+            let (head, tail) = input[..*len].split_at(len / 2);
+
+            let mut sha1 = Sha1::new();
+            sha1.update(&[]);
+            sha1.update(head);
+            sha1.update(&[]);
+            sha1.update(&[]);
+            sha1.update(tail);
+            sha1.update(&[]);
+            assert_eq!(sha1.finalize(), hash(&input[..*len]));
+        }
+    }
+
+    #[test]
+    fn test_words() {
+        let input = b"The quick brown fox jumps over the lazy cog";
+        let words = hash_words(input);
+        let bytes = hash(input);
+
+        for i in 0..5 {
+            let mut word = [0u8; 4];
+            word.copy_from_slice(&bytes[i * 4..i * 4 + 4]);
+            assert_eq!(words[i], u32::from_be_bytes(word));
+        }
+    }
+
+    #[test]
+    fn test_finalize_into() {
+        let mut out = [0xffu8; 20];
+
+        let mut sha1 = Sha1::new();
+        sha1.update(b"The quick brown fox jumps over the lazy dog");
+        sha1.finalize_into(&mut out);
+        assert_eq!(out, hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"));
+    }
+
+    #[test]
+    fn test_finalize_with_len() {
+        let mut sha1 = Sha1::new();
+        sha1.update(&[0x61; 100]);
+        sha1.update(b"abc");
+        let mut data = [0x61; 103];
+        data[100..].copy_from_slice(b"abc");
+        assert_eq!(sha1.finalize_with_len(), (hash(data), 103));
+
+        let resumed = Sha1::from_midstate(HASH_CONSTANTS, 1 << 32).unwrap();
+        assert_eq!(resumed.finalize_with_len().1, 1 << 32);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut sha1 = Sha1::new();
+
+        for message in [&b"abc"[..], &[0x33; 100], &[0x44; 64]].iter() {
+            sha1.update(message);
+            sha1.reset();
+
+            assert_eq!(sha1.state, HASH_CONSTANTS);
+            assert_eq!(sha1.block, [0u8; 64]);
+            assert_eq!(sha1.block_len, 0);
+            assert_eq!(sha1.length, 0);
+        }
+
+        sha1.update(b"The quick brown fox jumps over the lazy dog");
+        assert_eq!(
+            sha1.finalize(),
+            hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"),
+        );
+    }
+
+    #[test]
+    fn test_finalize_reset() {
+        let messages = [
+            &b"The quick brown fox jumps over the lazy dog"[..],
+            &[0x5c; 200],
+            b"",
+        ];
+
+        let mut sha1 = Sha1::new();
+        for message in messages.iter() {
+            sha1.update(message);
+            assert_eq!(sha1.finalize_reset(), hash(message));
+            assert_eq!(sha1.block, [0u8; 64]);
+            assert_eq!(sha1.block_len, 0);
+        }
+    }
+
+    #[test]
+    fn test_clone() {
+        let prefix = [0x61u8; 100];
+
+        let mut first = Sha1::new();
+        first.update(&prefix);
+        let mut second = first;
+
+        first.update(b"first suffix");
+        second.update(b"second");
+        assert_eq!(
+            first.finalize(),
+            hash([&prefix[..], b"first suffix"].concat())
+        );
+        assert_eq!(second.finalize(), hash([&prefix[..], b"second"].concat()));
+    }
+
+    #[test]
+    fn test_multi_block() {
+        assert_eq!(
+            hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            hex!("84983e441c3bd26ebaae4aa1f95129e5e54670f1"),
+        );
+        assert_eq!(
+            hash(&[b'a'; 1_000_000][..]),
+            hex!("34aa973cd4c4daa4f61eeb2bdbad27316534016f"),
+        );
+    }
+
+    #[test]
+    fn test_length_wraps() {
+        // A message of 2^61 bytes has a bit length of 2^64, which wraps to 0 in
+        // the length field, making the final block identical to that of "".
+        let sha1 = Sha1::from_midstate(HASH_CONSTANTS, 1 << 61).unwrap();
+        assert_eq!(sha1.finalize(), hash(b""));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[ignore = "hashes over 2 GiB of data"]
+    fn test_huge_input() {
+        let input = alloc::vec![0u8; (1 << 31) + 1];
+        assert_eq!(
+            hash(&input),
+            hex!("5007e5ebf10d0a9f01aef1c26c066169456d95ea"),
+        );
+    }
+
+    #[test]
+    fn test_length_past_u32() {
+        let mut sha1 = Sha1::from_midstate(HASH_CONSTANTS, (1 << 32) - 64).unwrap();
+        sha1.update(&[0u8; 128]);
+        assert_eq!(sha1.midstate().1, (1 << 32) + 64);
+    }
+
+    #[test]
+    #[ignore = "hashes 5 GiB of data"]
+    fn test_huge_stream() {
+        let mut chunk = [0u8; 1 << 20];
+        for (i, byte) in chunk.iter_mut().enumerate() {
+            *byte = (i % 251) as u8;
+        }
+
+        let mut sha1 = Sha1::new();
+        for _ in 0..5 * 1024 {
+            sha1.update(&chunk);
+        }
+        assert_eq!(
+            sha1.finalize(),
+            hex!("2f543f4bdea868d6489776b25864ee6599eb3aa8"),
+        );
+    }
+
+    #[test]
+    fn test_streaming() {
+        let input = b"abcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyz========abcdefghijklmnopqrstuvwxyz";
+
+        for split in 0..input.len() {
+            let mut sha1 = Sha1::new();
+            sha1.update(&input[..split]);
+            sha1.update(&input[split..]);
+            assert_eq!(sha1.finalize(), hash(input));
+        }
+    }
+
+    proptest::proptest! {
+        // Splitting a message into arbitrary chunks, including ones that
+        // straddle a partially filled block, must not change the digest.
+        #[test]
+        fn test_streaming_any_chunking(
+            message in proptest::collection::vec(proptest::num::u8::ANY, 0..600),
+            mut splits in proptest::collection::vec(0..600usize, 0..12),
+        ) {
+            splits.iter_mut().for_each(|split| *split %= message.len() + 1);
+            splits.sort_unstable();
+
+            let mut sha1 = Sha1::new();
+            let mut start = 0;
+            for &split in splits.iter().chain(core::iter::once(&message.len())) {
+                sha1.update(&message[start..split]);
+                start = split;
+            }
+            proptest::prop_assert_eq!(sha1.finalize(), hash(&message));
+        }
+    }
+
+    #[test]
+    fn test_streaming_straddling_block() {
+        let input = [0x7eu8; 200];
+
+        // Fill the buffer to 60 bytes, then update across the block boundary
+        let mut sha1 = Sha1::new();
+        sha1.update(&input[..60]);
+        sha1.update(&input[60..70]);
+        sha1.update(&input[70..]);
+        assert_eq!(sha1.finalize(), hash(input));
+    }
+
+    #[test]
+    fn test_streaming_bytewise() {
+        let input = b"The quick brown fox jumps over the lazy dog";
+
+        let mut sha1 = Sha1::new();
+        for byte in input.chunks(1) {
+            sha1.update(byte);
+        }
+        assert_eq!(
+            sha1.finalize(),
+            hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"),
+        );
+    }
+
+    #[test]
+    fn test_midstate() {
+        let input = [0x17u8; 300];
+
+        let mut sha1 = Sha1::new();
+        sha1.update(&input[..150]);
+        let (state, processed) = sha1.midstate();
+        assert_eq!(processed, 128);
+
+        let mut resumed = Sha1::from_midstate(state, processed).unwrap();
+        resumed.update(&input[128..]);
+        assert_eq!(resumed.finalize(), hash(input));
+    }
+
+    #[test]
+    fn test_midstate_unaligned() {
+        let (state, _) = Sha1::new().midstate();
+        assert_eq!(
+            Sha1::from_midstate(state, 100).err(),
+            Some(Sha1Error::BadMidstateLength)
+        );
+    }
+
+    #[test]
+    fn test_hash_iter() {
+        assert_eq!(hash_iter(b"abc".iter().copied()), hash(b"abc"));
+        assert_eq!(hash_iter(core::iter::empty()), hash(b""));
+        assert_eq!(
+            hash_iter(core::iter::repeat_n(b'a', 1_000_000)),
+            hex!("34aa973cd4c4daa4f61eeb2bdbad27316534016f"),
+        );
+    }
+
+    #[test]
+    fn test_hash_chunks() {
+        let mut message = [0u8; 1000];
+        for (i, byte) in message.iter_mut().enumerate() {
+            *byte = (i * 31) as u8;
+        }
+
+        for &size in [1, 7, 63, 64, 65, 333].iter() {
+            assert_eq!(hash_chunks(message.chunks(size)), hash(&message[..]));
+        }
+        // Empty chunks in between change nothing.
+        let parts: [&[u8]; 4] = [&message[..10], b"", &message[10..], b""];
+        assert_eq!(hash_chunks(parts.iter().copied()), hash(&message[..]));
+        assert_eq!(hash_chunks(core::iter::empty()), hash(b""));
+    }
+
+    #[test]
+    fn test_hash_blocks() {
+        let mut message = [0u8; 192];
+        for (i, byte) in message.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        let mut sha1 = Sha1::new();
+        sha1.update(&message);
+        assert_eq!(hash_blocks(&message), sha1.midstate().0);
+        assert_eq!(hash_blocks(&[]), HASH_CONSTANTS);
+    }
+
+    #[test]
+    #[should_panic(expected = "whole number of 64 byte blocks")]
+    fn test_hash_blocks_unaligned() {
+        hash_blocks(&[0; 65]);
+    }
+
+    #[test]
+    fn test_counters() {
+        let mut sha1 = Sha1::new();
+        assert_eq!((sha1.blocks_processed(), sha1.bytes_absorbed()), (0, 0));
+
+        sha1.update(&[0; 63]);
+        assert_eq!((sha1.blocks_processed(), sha1.bytes_absorbed()), (0, 63));
+        sha1.update(&[0; 1]);
+        assert_eq!((sha1.blocks_processed(), sha1.bytes_absorbed()), (1, 64));
+        sha1.update(&[0; 200]);
+        assert_eq!((sha1.blocks_processed(), sha1.bytes_absorbed()), (4, 264));
+
+        let resumed = Sha1::from_midstate(HASH_CONSTANTS, 640).unwrap();
+        assert_eq!(
+            (resumed.blocks_processed(), resumed.bytes_absorbed()),
+            (10, 640)
+        );
+    }
+
+    #[test]
+    fn test_default() {
+        let mut default = Sha1::default();
+        let mut new = Sha1::new();
+        default.update(b"abc");
+        new.update(b"abc");
+
+        assert_eq!(default.finalize(), new.finalize());
+        assert_eq!(Sha1::default().finalize(), hash(b""));
+    }
+
+    #[test]
+    fn test_size_constants() {
+        assert_eq!(Sha1::BLOCK_SIZE, 64);
+        assert_eq!(Sha1::OUTPUT_SIZE, hash(b"").len());
+        assert_eq!(crate::BLOCK_SIZE, Sha1::BLOCK_SIZE);
+        assert_eq!(crate::OUTPUT_SIZE, Sha1::OUTPUT_SIZE);
+    }
+
+    #[test]
+    fn test_size() {
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(core::mem::size_of::<Sha1>(), 104);
+        #[cfg(target_pointer_width = "32")]
+        assert_eq!(core::mem::size_of::<Sha1>(), 96);
+    }
+
+    #[test]
+    fn test_copy() {
+        let mut sha1 = Sha1::new();
+        sha1.update(b"abc");
+
+        let copy = sha1;
+        sha1.update(b"def");
+        assert_eq!(copy.finalize(), hash(b"abc"));
+        assert_eq!(sha1.finalize(), hash(b"abcdef"));
+    }
+
+    #[test]
+    fn test_update_many() {
+        let message = [0xa5u8; 300];
+        let slices: [&[u8]; 5] = [
+            &message[..1],
+            &message[1..64],
+            b"",
+            &message[64..200],
+            &message[200..],
+        ];
+
+        let mut many = Sha1::new();
+        many.update_many(&slices[..2]);
+        many.update_many(&slices[2..]);
+
+        let mut sequential = Sha1::new();
+        for data in slices.iter() {
+            sequential.update(data);
+        }
+
+        let digest = many.finalize();
+        assert_eq!(digest, sequential.finalize());
+        assert_eq!(digest, hash(&message[..]));
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut sha1 = Sha1::new();
+        sha1.extend(b"The quick brown fox ".iter().copied());
+
+        let before = sha1;
+        sha1.extend(core::iter::empty::<u8>());
+        assert_eq!(sha1.midstate(), before.midstate());
+        assert_eq!(
+            sha1.block[..sha1.block_len],
+            before.block[..before.block_len]
+        );
+        sha1.extend([&b"jumps over "[..], b"", b"the lazy dog"].iter().copied());
+        sha1.extend(core::iter::empty::<&[u8]>());
+
+        assert_eq!(
+            sha1.finalize(),
+            hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12")
+        );
+    }
+
+    #[test]
+    fn test_fmt_write() {
+        use core::fmt::Write;
+
+        let (a, b, c) = ("a", 'b', 'c');
+
+        let mut sha1 = Sha1::new();
+        write!(sha1, "{}{}", a, b).unwrap();
+        write!(sha1, "{}", c).unwrap();
+        assert_eq!(sha1.finalize(), hash(b"abc"));
+    }
+
+    #[test]
+    fn test_hash_as_ref() {
+        let expected = hex!("a9993e364706816aba3e25717850c26c9cd0d89d");
+
+        assert_eq!(hash("abc"), expected);
+        assert_eq!(hash(*b"abc"), expected);
+        assert_eq!(hash(&b"abc"[..]), expected);
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(hash(alloc::string::String::from("abc")), expected);
+            assert_eq!(hash(alloc::vec![b'a', b'b', b'c']), expected);
+        }
+    }
+
+    #[test]
+    fn test_hash_str() {
+        assert_eq!(
+            hash_str("The quick brown fox jumps over the lazy dog"),
+            hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"),
+        );
+        // Multibyte characters hash as their UTF-8 encoding.
+        assert_eq!(
+            hash_str("日本語"),
+            hash([0xe6, 0x97, 0xa5, 0xe6, 0x9c, 0xac, 0xe8, 0xaa, 0x9e])
+        );
+        assert_eq!(
+            hash_str("日本語"),
+            hex!("c12140a0ffb4e56481b4fe0a7a25040c2eafa9ca"),
+        );
+    }
+
+    #[test]
+    fn test_try_hash() {
+        assert_eq!(try_hash(b""), Ok(hash(b"")));
+        assert_eq!(try_hash(&[7; 200]), Ok(hash([7; 200])));
+
+        // The limit is the longest message whose bit length fits in 64 bits
+        assert!(MAX_INPUT_LEN.checked_mul(8).is_some());
+        assert!((MAX_INPUT_LEN + 1).checked_mul(8).is_none());
+    }
+
+    // Expected values from RFC 6234 and a bit-level reference implementation.
+    #[test]
+    fn test_hash_bits() {
+        assert_eq!(
+            hash_bits(&hex!("49b2aec2594bbe3a3b117542d94ac880"), 123),
+            hex!("6239781e03729919c01955b3ffa8acb60b988340"),
+        );
+        // Padding spills into a second block once the tail reaches 448 bits.
+        assert_eq!(
+            hash_bits(&[0xff; 56], 447),
+            hex!("534b3c083af50eb4d8d19f9059e008b1f01a2ff4"),
+        );
+        assert_eq!(
+            hash_bits(&[0xff; 64], 511),
+            hex!("248cac4928aa8b1185f27adee22fa222b91f5a9b"),
+        );
+        // Bits past bit_len are ignored.
+        assert_eq!(hash_bits(&[0x9f], 5), hash_bits(&[0x98], 5));
+        assert_eq!(hash_bits(b"abcd", 24), hash(b"abc"));
+        assert_eq!(hash_bits(b"", 0), hash(b""));
+    }
+
+    #[test]
+    #[should_panic(expected = "longer than the input")]
+    fn test_hash_bits_too_long() {
+        hash_bits(b"abc", 25);
+    }
+
+    #[test]
+    fn test_hash_vectored() {
+        let mut message = [0u8; 300];
+        for (i, b) in message.iter_mut().enumerate() {
+            *b = (i * 7) as u8;
+        }
+
+        let (a, rest) = message.split_at(10);
+        let (b, rest) = rest.split_at(60);
+        let (c, d) = rest.split_at(130);
+        let partitions: [&[&[u8]]; 4] = [
+            &[],
+            &[&message],
+            &[a, b, c, d],
+            &[&[], a, &[], b, c, &[], d, &[]],
+        ];
+
+        assert_eq!(hash_vectored(partitions[0]), hash(b""));
+        for slices in partitions[1..].iter() {
+            assert_eq!(hash_vectored(slices), hash(message));
+        }
+    }
+
+    #[test]
+    fn test_sha1_macro() {
+        assert_eq!(crate::sha1!(b"ab", b"c"), hash(b"abc"));
+        assert_eq!(crate::sha1!("a", [b'b'], &b"c"[..],), hash(b"abc"));
+        assert_eq!(crate::sha1!([0x5au8; 100]), hash([0x5au8; 100]));
+        assert_eq!(crate::sha1!(), hash(b""));
+    }
+
+    #[test]
+    fn test_hash_truncated() {
+        let digest = hash(b"abc");
+
+        assert_eq!(hash_truncated::<0>(b"abc"), [0u8; 0]);
+        assert_eq!(hash_truncated::<4>(b"abc"), digest[..4]);
+        assert_eq!(hash_truncated::<20>(b"abc"), digest);
+    }
+
+    #[test]
+    fn test_compress() {
+        let data = [0x5au8; 128];
+
+        let mut state = HASH_CONSTANTS;
+        compress(&mut state, &[0x5a; 64]);
+        compress(&mut state, &[0x5a; 64]);
+
+        let mut sha1 = Sha1::new();
+        sha1.update(&data);
+        assert_eq!(sha1.midstate(), (state, 128));
+    }
+
+    #[cfg(any(
+        all(feature = "sha_ni", target_arch = "x86_64"),
+        all(feature = "armv8_crypto", target_arch = "aarch64"),
+    ))]
+    #[test]
+    fn test_self_test() {
+        let soft: crate::CompressFn = |state, block| crate::update_hash_soft(state, block);
+        assert!(crate::self_test(soft));
+        if let Some(accelerated) = crate::accelerated_compress() {
+            assert!(crate::self_test(accelerated));
+        }
+    }
+
+    #[test]
+    fn test_compress_blocks() {
+        let mut blocks = [[0u8; 64]; 4];
+        for (i, block) in blocks.iter_mut().enumerate() {
+            block.fill(i as u8);
+        }
+
+        let mut state = HASH_CONSTANTS;
+        compress_blocks(&mut state, &blocks);
+
+        let mut expected = HASH_CONSTANTS;
+        for block in blocks.iter() {
+            compress(&mut expected, block);
+        }
+        assert_eq!(state, expected);
+
+        compress_blocks(&mut state, &[]);
+        assert_eq!(state, expected);
+    }
+
+    #[test]
+    fn test_raw_state() {
+        let mut sha1 = Sha1::new();
+        assert_eq!(sha1.state(), HASH_CONSTANTS);
+
+        // A partial block is only buffered, so the state doesn't move.
+        sha1.update(&[0x5a; 63]);
+        assert_eq!(sha1.state(), HASH_CONSTANTS);
+
+        sha1.update(&[0x5a]);
+        let mut expected = HASH_CONSTANTS;
+        compress(&mut expected, &[0x5a; 64]);
+        assert_eq!(sha1.state(), expected);
+    }
+
+    #[test]
+    fn test_with_iv() {
+        let iv = [1, 2, 3, 4, 5];
+
+        let mut block = [0u8; 64];
+        block[..3].copy_from_slice(b"abc");
+        block[3] = 0x80;
+        block[63] = 24;
+        let mut expected = iv;
+        compress(&mut expected, &block);
+
+        let mut sha1 = Sha1::with_iv(iv);
+        sha1.update(b"abc");
+        assert_eq!(sha1.finalize_words(), expected);
+    }
+}
+
+// Fixed vectors for big-endian targets. Every byte-to-word conversion in the
+// crate is explicit, so these hold on any target, but they pin down the
+// word assembly, the length field and the digest serialization separately
+// where a native-endian shortcut would show up first.
+#[cfg(all(test, target_endian = "big"))]
+mod big_endian_tests {
+    use crate::{
+        compress, hash, hash_const, hash_words, padding_blocks, write_digest, Sha1, HASH_CONSTANTS,
+    };
+    use hex_literal::hex;
+
+    #[test]
+    fn test_vectors() {
+        assert_eq!(hash(b""), hex!("da39a3ee5e6b4b0d3255bfef95601890afd80709"));
+        assert_eq!(
+            hash(b"abc"),
+            hex!("a9993e364706816aba3e25717850c26c9cd0d89d")
+        );
+        assert_eq!(
+            hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            hex!("84983e441c3bd26ebaae4aa1f95129e5e54670f1"),
+        );
+        assert_eq!(
+            hash_const(b"abc"),
+            hex!("a9993e364706816aba3e25717850c26c9cd0d89d")
+        );
+
+        let mut sha1 = Sha1::new();
+        for byte in b"abc".chunks(1) {
+            sha1.update(byte);
+        }
+        assert_eq!(
+            sha1.finalize(),
+            hex!("a9993e364706816aba3e25717850c26c9cd0d89d")
+        );
+    }
+
+    #[test]
+    fn test_word_assembly() {
+        // Only the first message word is set, to 0x61626380 ("abc" and the
+        // terminator), so the bytes must be read most significant first.
+        let mut block = [0u8; 64];
+        block[..4].copy_from_slice(&0x6162_6380u32.to_be_bytes());
+        block[63] = 24;
+
+        let mut state = HASH_CONSTANTS;
+        compress(&mut state, &block);
+        assert_eq!(
+            state,
+            [0xa9993e36, 0x4706816a, 0xba3e2571, 0x7850c26c, 0x9cd0d89d]
+        );
+        assert_eq!(hash_words(b"abc"), state);
+    }
+
+    #[test]
+    fn test_length_field() {
+        let mut blocks = [[0u8; 64]; 2];
+        assert_eq!(
+            padding_blocks(b"abc", 0x0102_0304_0506_0708, &mut blocks),
+            1
+        );
+        assert_eq!(blocks[0][56..], hex!("0810182028303840"));
+    }
+
+    #[test]
+    fn test_digest_serialization() {
+        let mut out = [0u8; 20];
+        write_digest(
+            &[0x01020304, 0x05060708, 0x090a0b0c, 0x0d0e0f10, 0x11121314],
+            &mut out,
+        );
+        assert_eq!(out, hex!("0102030405060708090a0b0c0d0e0f1011121314"));
+    }
 }
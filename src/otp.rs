@@ -0,0 +1,184 @@
+use crate::hmac;
+use crate::verify::ct_eq;
+
+/// Generates an HMAC-based one-time password as described in RFC 4226.
+///
+/// The HMAC-SHA1 of the big-endian `counter` is dynamically truncated to a
+/// 31 bit value and reduced modulo 10^`digits`. Callers displaying the code
+/// should left-pad it with zeros to `digits` characters.
+///
+/// # Arguments
+///
+/// *  `secret` - Byte slice holding the shared secret
+/// *  `counter` - The moving factor shared with the verifier
+/// *  `digits` - Number of decimal digits in the code, usually 6 to 8
+///
+/// # Panics
+///
+/// Panics if `digits` is greater than 9.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::hotp;
+///
+/// assert_eq!(hotp(b"12345678901234567890", 0, 6), 755224);
+/// ```
+///
+pub fn hotp(secret: &[u8], counter: u64, digits: u32) -> u32 {
+    assert!(digits <= 9, "HOTP codes are limited to 9 digits");
+
+    let mac = hmac(secret, &counter.to_be_bytes());
+
+    let offset = (mac[19] & 0xf) as usize;
+    let code = u32::from_be_bytes([
+        mac[offset] & 0x7f,
+        mac[offset + 1],
+        mac[offset + 2],
+        mac[offset + 3],
+    ]);
+
+    code % 10u32.pow(digits)
+}
+
+/// Generates a time-based one-time password as described in RFC 6238.
+///
+/// This is [`hotp`] with the counter set to the number of whole `step`
+/// second periods since the Unix epoch.
+///
+/// # Arguments
+///
+/// *  `secret` - Byte slice holding the shared secret
+/// *  `unix_time` - Current time in seconds since the Unix epoch
+/// *  `step` - Length of each time step in seconds, usually 30
+/// *  `digits` - Number of decimal digits in the code, usually 6 to 8
+///
+/// # Panics
+///
+/// Panics if `step` is zero or `digits` is greater than 9.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::totp;
+///
+/// assert_eq!(totp(b"12345678901234567890", 59, 30, 8), 94287082);
+/// ```
+///
+pub fn totp(secret: &[u8], unix_time: u64, step: u64, digits: u32) -> u32 {
+    hotp(secret, unix_time / step, digits)
+}
+
+/// Checks a time-based one-time password against the current time step and
+/// `skew` steps either side of it, to tolerate clock drift.
+///
+/// Every candidate in the window is computed and compared in constant time,
+/// so the time taken does not reveal which step matched, if any.
+///
+/// # Arguments
+///
+/// *  `secret` - Byte slice holding the shared secret
+/// *  `code` - The code supplied by the client
+/// *  `unix_time` - Current time in seconds since the Unix epoch
+/// *  `step` - Length of each time step in seconds, usually 30
+/// *  `digits` - Number of decimal digits in the code, usually 6 to 8
+/// *  `skew` - Number of steps before and after the current one to accept
+///
+/// # Panics
+///
+/// Panics if `step` is zero or `digits` is greater than 9.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::totp_verify;
+///
+/// let secret = b"12345678901234567890";
+///
+/// // Code from the previous step is accepted with a skew of one
+/// assert!(totp_verify(secret, 94287082, 89, 30, 8, 1));
+/// assert!(!totp_verify(secret, 94287082, 89, 30, 8, 0));
+/// ```
+///
+pub fn totp_verify(
+    secret: &[u8],
+    code: u32,
+    unix_time: u64,
+    step: u64,
+    digits: u32,
+    skew: u8,
+) -> bool {
+    let current = unix_time / step;
+    let first = current.saturating_sub(skew as u64);
+    let last = current.saturating_add(skew as u64);
+
+    let mut matched = false;
+    for counter in first..=last {
+        let expected = hotp(secret, counter, digits);
+        matched |= ct_eq(&expected.to_be_bytes(), &code.to_be_bytes());
+    }
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hotp, totp, totp_verify};
+
+    const SECRET: &[u8] = b"12345678901234567890";
+
+    // RFC 4226 appendix D.
+    #[test]
+    fn test_rfc4226() {
+        let expected = [
+            755224, 287082, 359152, 969429, 338314, 254676, 287922, 162583, 399871, 520489,
+        ];
+
+        for (counter, code) in expected.iter().enumerate() {
+            assert_eq!(hotp(SECRET, counter as u64, 6), *code);
+        }
+    }
+
+    #[test]
+    fn test_digits() {
+        // Truncated value for counter 0 from RFC 4226 appendix D
+        assert_eq!(hotp(SECRET, 0, 7), 4755224);
+        assert_eq!(hotp(SECRET, 0, 8), 84755224);
+        assert_eq!(hotp(SECRET, 0, 9), 284755224);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_many_digits() {
+        hotp(SECRET, 0, 10);
+    }
+
+    // SHA-1 rows of RFC 6238 appendix B.
+    #[test]
+    fn test_rfc6238() {
+        let expected = [
+            (59, 94287082),
+            (1111111109, 7081804),
+            (1111111111, 14050471),
+            (1234567890, 89005924),
+            (2000000000, 69279037),
+            (20000000000, 65353130),
+        ];
+
+        for (time, code) in expected.iter() {
+            assert_eq!(totp(SECRET, *time, 30, 8), *code);
+        }
+    }
+
+    #[test]
+    fn test_totp_skew() {
+        // 1111111109 and 1111111111 fall in adjacent steps
+        assert!(totp_verify(SECRET, 7081804, 1111111109, 30, 8, 0));
+        assert!(!totp_verify(SECRET, 7081804, 1111111111, 30, 8, 0));
+        assert!(totp_verify(SECRET, 7081804, 1111111111, 30, 8, 1));
+        assert!(totp_verify(SECRET, 14050471, 1111111109, 30, 8, 1));
+
+        // Window is clamped at the epoch
+        assert!(totp_verify(SECRET, 94287082, 0, 30, 8, 1));
+        assert!(!totp_verify(SECRET, 12345678, 59, 30, 8, 3));
+    }
+}
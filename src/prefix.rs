@@ -0,0 +1,93 @@
+use crate::Sha1;
+
+/// A message prefix whose complete blocks have already been compressed, so
+/// that many messages sharing it can be hashed in time proportional to
+/// their suffix only.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, Sha1Prefix};
+///
+/// let header = [0xffu8; 300];
+/// let prefix = Sha1Prefix::new(&header);
+///
+/// for body in [&b"first"[..], b"second"].iter() {
+///     assert_eq!(prefix.hash(body), hash(&[&header[..], body].concat()));
+/// }
+/// ```
+///
+#[derive(Clone)]
+pub struct Sha1Prefix {
+    // Holds the midstate after the prefix's whole blocks, plus its trailing
+    // partial block.
+    sha1: Sha1,
+}
+
+impl Sha1Prefix {
+    /// Processes the complete 64 byte blocks of `prefix` and buffers the
+    /// remaining bytes.
+    ///
+    /// # Arguments
+    ///
+    /// *  `prefix` - Byte slice holding the shared prefix
+    ///
+    pub fn new(prefix: &[u8]) -> Self {
+        let mut sha1 = Sha1::new();
+        sha1.update(prefix);
+        Sha1Prefix { sha1 }
+    }
+
+    /// Returns the digest of the prefix followed by `suffix`.
+    ///
+    /// # Arguments
+    ///
+    /// *  `suffix` - Byte slice holding the rest of the message
+    ///
+    pub fn hash(&self, suffix: &[u8]) -> [u8; 20] {
+        let mut sha1 = self.hasher();
+        sha1.update(suffix);
+        sha1.finalize()
+    }
+
+    /// Returns a streaming hasher that has already been fed the prefix, for
+    /// suffixes that arrive in pieces.
+    pub fn hasher(&self) -> Sha1 {
+        self.sha1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, Sha1Prefix};
+
+    #[test]
+    fn test_prefix() {
+        let mut message = [0u8; 400];
+        for (i, b) in message.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+
+        for split in [0, 1, 63, 64, 65, 128, 200].iter() {
+            let (head, tail) = message.split_at(*split);
+            let prefix = Sha1Prefix::new(head);
+
+            assert_eq!(prefix.hash(tail), hash(message));
+            assert_eq!(prefix.hash(b""), hash(head));
+        }
+    }
+
+    #[test]
+    fn test_hasher() {
+        let prefix = Sha1Prefix::new(b"The quick brown fox ");
+
+        let mut sha1 = prefix.hasher();
+        sha1.update(b"jumps over ");
+        sha1.update(b"the lazy dog");
+
+        assert_eq!(
+            sha1.finalize(),
+            hash(b"The quick brown fox jumps over the lazy dog")
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use crate::encoding::{encode_hex, HEX_LOWER};
+use crate::{Digest, Sha1};
+use core::fmt;
+use core::str;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+/// Digests serialize as a lowercase hex string in human-readable formats and
+/// as 20 raw bytes otherwise.
+impl Serialize for Digest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let mut out = [0u8; 40];
+            encode_hex(self.as_ref(), &mut out, HEX_LOWER);
+            serializer.serialize_str(str::from_utf8(&out).unwrap())
+        } else {
+            serializer.serialize_bytes(self.as_ref())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Digest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(DigestVisitor)
+        } else {
+            deserializer.deserialize_bytes(DigestVisitor)
+        }
+    }
+}
+
+struct DigestVisitor;
+
+impl<'de> Visitor<'de> for DigestVisitor {
+    type Value = Digest;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a SHA-1 digest as 40 hex characters or 20 bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Digest, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Digest, E> {
+        if v.len() != 20 {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+
+        let mut bytes = [0u8; 20];
+        bytes.copy_from_slice(v);
+        Ok(Digest::from(bytes))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Digest, A::Error> {
+        let mut bytes = [0u8; 20];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(21, &self));
+        }
+        Ok(Digest::from(bytes))
+    }
+}
+
+/// A hasher serializes as a `(state, buffered, length)` tuple: the five
+/// state words, the bytes of the partial block waiting to be compressed, and
+/// the total number of bytes fed so far. Deserializing it gives a hasher
+/// that carries on exactly where the original left off.
+///
+/// The state is as sensitive as the data hashed so far, so only persist it
+/// where the input itself could be stored.
+impl Serialize for Sha1 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(&self.state)?;
+        tuple.serialize_element(&PartialBlock(&self.block[..self.block_len]))?;
+        tuple.serialize_element(&self.length)?;
+        tuple.end()
+    }
+}
+
+struct PartialBlock<'a>(&'a [u8]);
+
+impl Serialize for PartialBlock<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Sha1 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(3, Sha1Visitor)
+    }
+}
+
+struct Sha1Visitor;
+
+impl<'de> Visitor<'de> for Sha1Visitor {
+    type Value = Sha1;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a SHA-1 hasher state")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Sha1, A::Error> {
+        let state: [u32; 5] = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let (block, block_len) = seq
+            .next_element::<OwnedPartialBlock>()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?
+            .0;
+        let length: u64 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+
+        if length % 64 != block_len as u64 {
+            return Err(de::Error::custom(
+                "hasher length does not match the buffered bytes",
+            ));
+        }
+
+        Ok(Sha1 {
+            state,
+            block,
+            block_len,
+            length,
+        })
+    }
+}
+
+struct OwnedPartialBlock(([u8; 64], usize));
+
+impl<'de> Deserialize<'de> for OwnedPartialBlock {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(PartialBlockVisitor)
+    }
+}
+
+struct PartialBlockVisitor;
+
+impl<'de> Visitor<'de> for PartialBlockVisitor {
+    type Value = OwnedPartialBlock;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("at most 63 buffered bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<OwnedPartialBlock, E> {
+        if v.len() >= 64 {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+
+        let mut block = [0u8; 64];
+        block[..v.len()].copy_from_slice(v);
+        Ok(OwnedPartialBlock((block, v.len())))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<OwnedPartialBlock, A::Error> {
+        let mut block = [0u8; 64];
+        let mut len = 0;
+        while let Some(byte) = seq.next_element()? {
+            if len == 63 {
+                return Err(de::Error::invalid_length(64, &self));
+            }
+            block[len] = byte;
+            len += 1;
+        }
+        Ok(OwnedPartialBlock((block, len)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, hash_digest, Digest, Sha1};
+    use hex_literal::hex;
+    use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
+
+    #[test]
+    fn test_readable() {
+        let digest = hash_digest(b"The quick brown fox jumps over the lazy dog");
+
+        assert_tokens(
+            &digest.readable(),
+            &[Token::Str("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12")],
+        );
+    }
+
+    #[test]
+    fn test_compact() {
+        let digest = hash_digest(b"The quick brown fox jumps over the lazy dog");
+
+        assert_tokens(
+            &digest.compact(),
+            &[Token::Bytes(&hex!(
+                "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"
+            ))],
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_de_tokens_error::<serde_test::Readable<Digest>>(
+            &[Token::Str("2fd4e1c67a2d28fced849ee1bb76e7391b93eb1g")],
+            "invalid character in encoded digest",
+        );
+        assert_de_tokens_error::<serde_test::Compact<Digest>>(
+            &[Token::Bytes(&[0u8; 19])],
+            "invalid length 19, expected a SHA-1 digest as 40 hex characters or 20 bytes",
+        );
+    }
+
+    #[test]
+    fn test_sha1_resume() {
+        let mut message = [0u8; 1000];
+        for (i, byte) in message.iter_mut().enumerate() {
+            *byte = (i * 13) as u8;
+        }
+
+        for &split in [0, 1, 63, 64, 100, 999].iter() {
+            let mut sha1 = Sha1::new();
+            sha1.update(&message[..split]);
+
+            let saved = serde_json::to_string(&sha1).unwrap();
+            let mut resumed: Sha1 = serde_json::from_str(&saved).unwrap();
+            resumed.update(&message[split..]);
+            assert_eq!(resumed.finalize(), hash(&message[..]));
+        }
+    }
+
+    #[test]
+    fn test_sha1_invalid() {
+        let header = [
+            Token::Tuple { len: 3 },
+            Token::Tuple { len: 5 },
+            Token::U32(0),
+            Token::U32(0),
+            Token::U32(0),
+            Token::U32(0),
+            Token::U32(0),
+            Token::TupleEnd,
+        ];
+
+        let mut tokens = [Token::Unit; 10];
+        tokens[..8].copy_from_slice(&header);
+        tokens[8] = Token::Bytes(b"abc");
+        tokens[9] = Token::U64(64);
+        assert_de_tokens_error::<Sha1>(&tokens, "hasher length does not match the buffered bytes");
+
+        let mut tokens = [Token::Unit; 9];
+        tokens[..8].copy_from_slice(&header);
+        tokens[8] = Token::Bytes(&[0; 64]);
+        assert_de_tokens_error::<Sha1>(
+            &tokens,
+            "invalid length 64, expected at most 63 buffered bytes",
+        );
+    }
+}
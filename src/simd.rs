@@ -0,0 +1,110 @@
+use crate::consts::ROUND_CONSTANTS;
+use core::simd::{simd_swizzle, u32x4};
+
+/// Alternative to the scalar `update_hash` that expands the message schedule
+/// four words at a time with `core::simd`.
+pub(crate) fn update_hash(hash: &mut [u32; 5], block: &[u8]) {
+    let w = expand_schedule(block);
+
+    let mut a = hash[0];
+    let mut b = hash[1];
+    let mut c = hash[2];
+    let mut d = hash[3];
+    let mut e = hash[4];
+
+    for (t, &x) in w.iter().enumerate() {
+        let (f, k) = match t / 20 {
+            0 => ((b & c) | (!b & d), ROUND_CONSTANTS[0]),
+            1 => (b ^ c ^ d, ROUND_CONSTANTS[1]),
+            2 => ((b & c) | (b & d) | (c & d), ROUND_CONSTANTS[2]),
+            _ => (b ^ c ^ d, ROUND_CONSTANTS[3]),
+        };
+
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(x)
+            .wrapping_add(k);
+
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    hash[0] = hash[0].wrapping_add(a);
+    hash[1] = hash[1].wrapping_add(b);
+    hash[2] = hash[2].wrapping_add(c);
+    hash[3] = hash[3].wrapping_add(d);
+    hash[4] = hash[4].wrapping_add(e);
+}
+
+fn rotate_left_1(v: u32x4) -> u32x4 {
+    (v << u32x4::splat(1)) | (v >> u32x4::splat(31))
+}
+
+/// Computes all 80 schedule words, four per step.
+///
+/// Words `t..t + 4` depend on `w[t - 3..t + 1]`, whose last lane is `w[t]`
+/// from the same step. That lane is computed as zero first and corrected
+/// afterwards, which works because the rotation distributes over XOR.
+fn expand_schedule(block: &[u8]) -> [u32; 80] {
+    let mut w = [u32x4::splat(0); 20];
+
+    for (i, v) in w[..4].iter_mut().enumerate() {
+        let mut words = [0u32; 4];
+        for (j, word) in words.iter_mut().enumerate() {
+            let t = (i * 4 + j) * 4;
+            *word = u32::from_be_bytes([block[t], block[t + 1], block[t + 2], block[t + 3]]);
+        }
+        *v = u32x4::from_array(words);
+    }
+
+    for i in 4..20 {
+        // w[t - 3], w[t - 2], w[t - 1], 0
+        let w3 = simd_swizzle!(w[i - 1], u32x4::splat(0), [1, 2, 3, 4]);
+        // w[t - 8], ..., w[t - 5]
+        let w8 = w[i - 2];
+        // w[t - 14], ..., w[t - 11]
+        let w14 = simd_swizzle!(w[i - 4], w[i - 3], [2, 3, 4, 5]);
+        // w[t - 16], ..., w[t - 13]
+        let w16 = w[i - 4];
+
+        let mut v = rotate_left_1(w3 ^ w8 ^ w14 ^ w16);
+        let carry = rotate_left_1(simd_swizzle!(u32x4::splat(0), v, [0, 0, 0, 4]));
+        v ^= carry;
+        w[i] = v;
+    }
+
+    let mut out = [0u32; 80];
+    for (chunk, v) in out.chunks_exact_mut(4).zip(w.iter()) {
+        chunk.copy_from_slice(v.as_array());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_schedule;
+
+    #[test]
+    fn test_expand_schedule() {
+        let mut block = [0u8; 64];
+        for (i, byte) in block.iter_mut().enumerate() {
+            *byte = (i as u8).wrapping_mul(151).wrapping_add(7);
+        }
+
+        let mut expected = [0u32; 80];
+        for (word, bytes) in expected.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for t in 16..80 {
+            expected[t] = (expected[t - 3] ^ expected[t - 8] ^ expected[t - 14] ^ expected[t - 16])
+                .rotate_left(1);
+        }
+
+        assert_eq!(expand_schedule(&block)[..], expected[..]);
+    }
+}
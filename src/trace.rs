@@ -0,0 +1,114 @@
+use crate::consts::ROUND_CONSTANTS;
+
+/// Runs the SHA-1 compression function like [`compress`](crate::compress),
+/// calling `trace` with the round index and the working variables
+/// `[a, b, c, d, e]` after each of the 80 rounds.
+///
+/// This always uses a plain scalar implementation and is much slower than
+/// `compress`. It is meant for studying or debugging the algorithm.
+///
+/// # Arguments
+///
+/// *  `state` - The chaining state to update in place
+/// *  `block` - The 64 byte block to process
+/// *  `trace` - Callback receiving the round index and working variables
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::compress_traced;
+///
+/// let mut block = [0u8; 64];
+/// block[..3].copy_from_slice(b"abc");
+/// block[3] = 0x80;
+/// block[63] = 24;
+///
+/// let mut state = bad_sha1::consts::HASH_CONSTANTS;
+/// compress_traced(&mut state, &block, &mut |t, [a, b, c, d, e]| {
+///     println!("t={:2} {:08x} {:08x} {:08x} {:08x} {:08x}", t, a, b, c, d, e);
+/// });
+/// ```
+///
+pub fn compress_traced(
+    state: &mut [u32; 5],
+    block: &[u8; 64],
+    trace: &mut dyn FnMut(usize, [u32; 5]),
+) {
+    let mut w = [0u32; 80];
+    for (t, word) in block.chunks_exact(4).enumerate() {
+        w[t] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for t in 16..80 {
+        w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+
+    for (t, &x) in w.iter().enumerate() {
+        let f = match t / 20 {
+            0 => (b & c) | (!b & d),
+            2 => (b & c) | (b & d) | (c & d),
+            _ => b ^ c ^ d,
+        };
+
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(x)
+            .wrapping_add(ROUND_CONSTANTS[t / 20]);
+
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+
+        trace(t, [a, b, c, d, e]);
+    }
+
+    for (h, v) in state.iter_mut().zip([a, b, c, d, e].iter()) {
+        *h = h.wrapping_add(*v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::HASH_CONSTANTS;
+    use crate::{compress, compress_traced};
+
+    // "abc" worked example from FIPS 180-2 appendix A.1.
+    #[test]
+    fn test_compress_traced() {
+        let mut block = [0u8; 64];
+        block[..3].copy_from_slice(b"abc");
+        block[3] = 0x80;
+        block[63] = 24;
+
+        let mut rounds = [[0u32; 5]; 80];
+        let mut calls = 0;
+        let mut state = HASH_CONSTANTS;
+        compress_traced(&mut state, &block, &mut |t, vars| {
+            assert_eq!(t, calls);
+            rounds[t] = vars;
+            calls += 1;
+        });
+
+        assert_eq!(calls, 80);
+        assert_eq!(
+            rounds[0],
+            [0x0116FC33, 0x67452301, 0x7BF36AE2, 0x98BADCFE, 0x10325476]
+        );
+        assert_eq!(
+            rounds[79],
+            [0x42541B35, 0x5738D5E1, 0x21834873, 0x681E6DF6, 0xD8FDF6AD]
+        );
+
+        let mut expected = HASH_CONSTANTS;
+        compress(&mut expected, &block);
+        assert_eq!(state, expected);
+        for (i, h) in expected.iter().enumerate() {
+            assert_eq!(*h, HASH_CONSTANTS[i].wrapping_add(rounds[79][i]));
+        }
+    }
+}
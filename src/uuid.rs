@@ -0,0 +1,80 @@
+use crate::Sha1;
+
+/// Namespace for fully-qualified domain names (RFC 4122 appendix C).
+pub const UUID_NAMESPACE_DNS: [u8; 16] = [
+    0x6b, 0xa7, 0xb8, 0x10, 0x9d, 0xad, 0x11, 0xd1, 0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8,
+];
+
+/// Namespace for URLs (RFC 4122 appendix C).
+pub const UUID_NAMESPACE_URL: [u8; 16] = [
+    0x6b, 0xa7, 0xb8, 0x11, 0x9d, 0xad, 0x11, 0xd1, 0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8,
+];
+
+/// Namespace for ISO OIDs (RFC 4122 appendix C).
+pub const UUID_NAMESPACE_OID: [u8; 16] = [
+    0x6b, 0xa7, 0xb8, 0x12, 0x9d, 0xad, 0x11, 0xd1, 0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8,
+];
+
+/// Namespace for X.500 distinguished names (RFC 4122 appendix C).
+pub const UUID_NAMESPACE_X500: [u8; 16] = [
+    0x6b, 0xa7, 0xb8, 0x14, 0x9d, 0xad, 0x11, 0xd1, 0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8,
+];
+
+/// Generates a name-based version 5 UUID as described in RFC 4122
+/// section 4.3, returning its 16 bytes in network order.
+///
+/// # Arguments
+///
+/// *  `namespace` - The namespace UUID, such as [`UUID_NAMESPACE_DNS`]
+/// *  `name` - Byte slice holding the name within the namespace
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{uuid_v5, UUID_NAMESPACE_DNS};
+/// use hex_literal::hex;
+///
+/// assert_eq!(
+///     uuid_v5(&UUID_NAMESPACE_DNS, b"www.example.com"),
+///     hex!("2ed6657d e927 568b 95e1 2665a8aea6a2"),
+/// );
+/// ```
+///
+pub fn uuid_v5(namespace: &[u8; 16], name: &[u8]) -> [u8; 16] {
+    let mut sha1 = Sha1::new();
+    sha1.update(namespace);
+    sha1.update(name);
+
+    let mut uuid = [0u8; 16];
+    uuid.copy_from_slice(&sha1.finalize()[..16]);
+    uuid[6] = (uuid[6] & 0x0f) | 0x50;
+    uuid[8] = (uuid[8] & 0x3f) | 0x80;
+    uuid
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{uuid_v5, UUID_NAMESPACE_DNS, UUID_NAMESPACE_URL};
+    use hex_literal::hex;
+
+    #[test]
+    fn test_uuid_v5() {
+        assert_eq!(
+            uuid_v5(&UUID_NAMESPACE_DNS, b"www.example.com"),
+            hex!("2ed6657de927568b95e12665a8aea6a2"),
+        );
+        assert_eq!(
+            uuid_v5(&UUID_NAMESPACE_URL, b"https://example.com/"),
+            hex!("dd2c1780811a529681c5178a0ef488bc"),
+        );
+    }
+
+    #[test]
+    fn test_version_and_variant() {
+        for name in [&b""[..], b"a", b"bad_sha1"].iter() {
+            let uuid = uuid_v5(&UUID_NAMESPACE_DNS, name);
+            assert_eq!(uuid[6] >> 4, 5);
+            assert_eq!(uuid[8] >> 6, 0b10);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::encoding::decode_hex;
+use crate::hash;
+use core::hint::black_box;
+
+/// Compares two byte slices of equal length without short-circuiting, so
+/// the time taken does not depend on where they first differ.
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    debug_assert_eq!(a.len(), b.len());
+
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    black_box(diff) == 0
+}
+
+/// Hashes the given input using SHA-1 and checks it against the expected
+/// digest in constant time.
+///
+/// All 20 bytes are compared regardless of where the first mismatch is, so
+/// this is safe to use for checking authentication tags.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+/// *  `expected` - The digest the input is expected to hash to
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::verify;
+/// use hex_literal::hex;
+///
+/// assert!(verify(
+///     b"The quick brown fox jumps over the lazy dog",
+///     &hex!("2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"),
+/// ));
+/// ```
+///
+pub fn verify(input: &[u8], expected: &[u8; 20]) -> bool {
+    ct_eq(&hash(input), expected)
+}
+
+/// Hashes the given input using SHA-1 and checks it in constant time
+/// against an expected digest given as 40 hexadecimal characters, in either
+/// case.
+///
+/// Malformed hex makes this return `false` rather than panic.
+///
+/// # Arguments
+///
+/// *  `input` - Byte slice holding input message
+/// *  `expected_hex` - The hex digest the input is expected to hash to
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::verify_hex;
+///
+/// assert!(verify_hex(
+///     b"The quick brown fox jumps over the lazy dog",
+///     "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12",
+/// ));
+/// ```
+///
+pub fn verify_hex(input: &[u8], expected_hex: &str) -> bool {
+    let mut expected = [0u8; 20];
+    if decode_hex(expected_hex.as_bytes(), &mut expected).is_err() {
+        return false;
+    }
+    verify(input, &expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, verify, verify_hex};
+
+    #[test]
+    fn test_verify() {
+        let input = b"The quick brown fox jumps over the lazy dog";
+        let digest = hash(input);
+
+        assert!(verify(input, &digest));
+        for i in 0..20 {
+            let mut wrong = digest;
+            wrong[i] ^= 0x01;
+            assert!(!verify(input, &wrong));
+        }
+    }
+
+    #[test]
+    fn test_verify_hex() {
+        let input = b"abc";
+
+        assert!(verify_hex(
+            input,
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        ));
+        assert!(verify_hex(
+            input,
+            "A9993E364706816ABA3E25717850C26C9CD0D89D"
+        ));
+        assert!(!verify_hex(
+            input,
+            "a9993e364706816aba3e25717850c26c9cd0d89e"
+        ));
+        assert!(!verify_hex(
+            input,
+            "a9993e364706816aba3e25717850c26c9cd0d89"
+        ));
+        assert!(!verify_hex(
+            input,
+            "a9993e364706816aba3e25717850c26c9cd0d8zz"
+        ));
+        assert!(!verify_hex(input, ""));
+    }
+}
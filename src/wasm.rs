@@ -0,0 +1,68 @@
+//! JavaScript bindings generated with `wasm_bindgen`.
+//!
+//! Byte slices arrive from JavaScript as `Uint8Array`s and digests are
+//! returned as 20 byte `Uint8Array`s.
+
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+/// Hashes the given bytes using SHA-1, returning the 20 byte digest.
+///
+/// # Arguments
+///
+/// *  `bytes` - Byte slice holding input message
+///
+#[wasm_bindgen]
+pub fn hash(bytes: &[u8]) -> Vec<u8> {
+    crate::hash(bytes).to_vec()
+}
+
+/// Streaming SHA-1 hasher, exported to JavaScript as `Sha1`.
+#[wasm_bindgen(js_name = Sha1)]
+pub struct WasmSha1 {
+    sha1: crate::Sha1,
+}
+
+#[wasm_bindgen(js_class = Sha1)]
+impl WasmSha1 {
+    /// Creates a new hasher.
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        WasmSha1 {
+            sha1: crate::Sha1::new(),
+        }
+    }
+
+    /// Feeds the next chunk of the message into the hasher.
+    ///
+    /// # Arguments
+    ///
+    /// *  `data` - Byte slice holding the next part of the message
+    ///
+    pub fn update(&mut self, data: &[u8]) {
+        self.sha1.update(data);
+    }
+
+    /// Returns the 20 byte digest. The JavaScript object is consumed and
+    /// cannot be used afterwards.
+    pub fn finalize(self) -> Vec<u8> {
+        self.sha1.finalize().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash, WasmSha1};
+
+    #[test]
+    fn test_bindings() {
+        let mut sha1 = WasmSha1::new();
+        sha1.update(b"The quick brown fox ");
+        sha1.update(b"jumps over the lazy dog");
+
+        let expected = crate::hash(b"The quick brown fox jumps over the lazy dog");
+        assert_eq!(sha1.finalize(), expected);
+        assert_eq!(hash(b"abc"), crate::hash(b"abc"));
+    }
+}
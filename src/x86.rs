@@ -0,0 +1,125 @@
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Returns whether the CPU supports the SHA extensions.
+pub(crate) fn sha_ni_available() -> bool {
+    is_x86_feature_detected!("sha")
+        && is_x86_feature_detected!("sse2")
+        && is_x86_feature_detected!("ssse3")
+        && is_x86_feature_detected!("sse4.1")
+}
+
+/// Runs four rounds, first deriving `e` from the previous `abcd` in `h1`.
+macro_rules! rounds4 {
+    ($h0:ident, $h1:ident, $wk:expr, $i:expr) => {
+        _mm_sha1rnds4_epu32($h0, _mm_sha1nexte_epu32($h1, $wk), $i)
+    };
+}
+
+/// Computes the next four schedule words from the previous sixteen.
+macro_rules! schedule {
+    ($v0:expr, $v1:expr, $v2:expr, $v3:expr) => {
+        _mm_sha1msg2_epu32(_mm_xor_si128(_mm_sha1msg1_epu32($v0, $v1), $v2), $v3)
+    };
+}
+
+macro_rules! schedule_rounds4 {
+    ($h0:ident, $h1:ident, $w0:expr, $w1:expr, $w2:expr, $w3:expr, $w4:expr, $i:expr) => {
+        $w4 = schedule!($w0, $w1, $w2, $w3);
+        $h1 = rounds4!($h0, $h1, $w4, $i);
+    };
+}
+
+/// Alternative to the scalar `update_hash` using the `sha1rnds4`,
+/// `sha1nexte` and `sha1msg1/2` instructions.
+///
+/// # Safety
+///
+/// The CPU must support the `sha`, `sse2`, `ssse3` and `sse4.1` features,
+/// see [`sha_ni_available`].
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+pub(crate) unsafe fn update_hash(hash: &mut [u32; 5], block: &[u8]) {
+    let block = &block[..64];
+    let mask = _mm_set_epi64x(0x0001_0203_0405_0607, 0x0809_0a0b_0c0d_0e0f);
+
+    let abcd = _mm_set_epi32(
+        hash[0] as i32,
+        hash[1] as i32,
+        hash[2] as i32,
+        hash[3] as i32,
+    );
+    let e = _mm_set_epi32(hash[4] as i32, 0, 0, 0);
+
+    let ptr = block.as_ptr() as *const __m128i;
+    let mut w0 = _mm_shuffle_epi8(_mm_loadu_si128(ptr), mask);
+    let mut w1 = _mm_shuffle_epi8(_mm_loadu_si128(ptr.add(1)), mask);
+    let mut w2 = _mm_shuffle_epi8(_mm_loadu_si128(ptr.add(2)), mask);
+    let mut w3 = _mm_shuffle_epi8(_mm_loadu_si128(ptr.add(3)), mask);
+    let mut w4;
+
+    let mut h0 = abcd;
+    let mut h1 = _mm_add_epi32(e, w0);
+
+    // Rounds 0..20
+    h1 = _mm_sha1rnds4_epu32(h0, h1, 0);
+    h0 = rounds4!(h1, h0, w1, 0);
+    h1 = rounds4!(h0, h1, w2, 0);
+    h0 = rounds4!(h1, h0, w3, 0);
+    schedule_rounds4!(h0, h1, w0, w1, w2, w3, w4, 0);
+
+    // Rounds 20..40
+    schedule_rounds4!(h1, h0, w1, w2, w3, w4, w0, 1);
+    schedule_rounds4!(h0, h1, w2, w3, w4, w0, w1, 1);
+    schedule_rounds4!(h1, h0, w3, w4, w0, w1, w2, 1);
+    schedule_rounds4!(h0, h1, w4, w0, w1, w2, w3, 1);
+    schedule_rounds4!(h1, h0, w0, w1, w2, w3, w4, 1);
+
+    // Rounds 40..60
+    schedule_rounds4!(h0, h1, w1, w2, w3, w4, w0, 2);
+    schedule_rounds4!(h1, h0, w2, w3, w4, w0, w1, 2);
+    schedule_rounds4!(h0, h1, w3, w4, w0, w1, w2, 2);
+    schedule_rounds4!(h1, h0, w4, w0, w1, w2, w3, 2);
+    schedule_rounds4!(h0, h1, w0, w1, w2, w3, w4, 2);
+
+    // Rounds 60..80
+    schedule_rounds4!(h1, h0, w1, w2, w3, w4, w0, 3);
+    schedule_rounds4!(h0, h1, w2, w3, w4, w0, w1, 3);
+    schedule_rounds4!(h1, h0, w3, w4, w0, w1, w2, 3);
+    schedule_rounds4!(h0, h1, w4, w0, w1, w2, w3, 3);
+    schedule_rounds4!(h1, h0, w0, w1, w2, w3, w4, 3);
+
+    let abcd = _mm_add_epi32(abcd, h0);
+    let e = _mm_sha1nexte_epu32(h1, e);
+
+    hash[0] = _mm_extract_epi32(abcd, 3) as u32;
+    hash[1] = _mm_extract_epi32(abcd, 2) as u32;
+    hash[2] = _mm_extract_epi32(abcd, 1) as u32;
+    hash[3] = _mm_extract_epi32(abcd, 0) as u32;
+    hash[4] = _mm_extract_epi32(e, 3) as u32;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sha_ni_available, update_hash};
+    use crate::{update_hash_soft, HASH_CONSTANTS};
+
+    #[test]
+    fn test_matches_scalar() {
+        if !sha_ni_available() {
+            return;
+        }
+
+        let mut block = [0u8; 64];
+        let mut expected = HASH_CONSTANTS;
+        let mut actual = HASH_CONSTANTS;
+
+        for round in 0..100u8 {
+            for (i, byte) in block.iter_mut().enumerate() {
+                *byte = (i as u8).wrapping_mul(round).wrapping_add(round);
+            }
+            update_hash_soft(&mut expected, &block);
+            unsafe { update_hash(&mut actual, &block) };
+            assert_eq!(actual, expected);
+        }
+    }
+}
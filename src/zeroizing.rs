@@ -0,0 +1,161 @@
+use crate::{write_digest, Sha1};
+use core::ops::{Deref, DerefMut};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Overwrites the state, buffered input and counters with zeros.
+impl Zeroize for Sha1 {
+    fn zeroize(&mut self) {
+        self.state.zeroize();
+        self.block.zeroize();
+        self.block_len.zeroize();
+        self.length.zeroize();
+    }
+}
+
+/// A [`Sha1`] that is overwritten with zeros when dropped, for hashing
+/// secret material.
+///
+/// `Sha1` itself is `Copy` and so cannot wipe itself on drop; wrap it in
+/// this type instead. It dereferences to the inner hasher, so every `Sha1`
+/// method is available. The `finalize` methods are provided here as well so
+/// they run on the wrapped hasher in place, rather than on a copy taken
+/// through the dereference, and wipe it once the digest has been produced.
+///
+/// # Examples
+///
+/// ```
+/// use bad_sha1::{hash, ZeroizingSha1};
+///
+/// let mut sha1 = ZeroizingSha1::new();
+/// sha1.update(b"secret key material");
+///
+/// assert_eq!(sha1.finalize(), hash(b"secret key material"));
+/// ```
+///
+#[derive(Clone, Default)]
+pub struct ZeroizingSha1(Sha1);
+
+impl ZeroizingSha1 {
+    /// Creates a new hasher with the standard SHA-1 initial state.
+    pub fn new() -> Self {
+        ZeroizingSha1(Sha1::new())
+    }
+
+    /// Pads the message, processes the final block(s) and returns the
+    /// 20 byte digest, then wipes the hasher.
+    pub fn finalize(self) -> [u8; 20] {
+        let mut output = [0u8; 20];
+        self.finalize_into(&mut output);
+        output
+    }
+
+    /// Pads the message, processes the final block(s) and writes the 20 byte
+    /// digest into `out`, then wipes the hasher.
+    ///
+    /// # Arguments
+    ///
+    /// *  `out` - Buffer the digest is written to
+    ///
+    pub fn finalize_into(mut self, out: &mut [u8; 20]) {
+        self.0.pad();
+        write_digest(&self.0.state, out);
+    }
+
+    /// Pads the message, processes the final block(s) and returns the
+    /// 20 byte digest along with the total number of bytes hashed, then
+    /// wipes the hasher.
+    pub fn finalize_with_len(self) -> ([u8; 20], u64) {
+        let length = self.0.length;
+        (self.finalize(), length)
+    }
+
+    /// Pads the message, processes the final block(s) and returns the
+    /// digest as five words, then wipes the hasher.
+    pub fn finalize_words(mut self) -> [u32; 5] {
+        self.0.pad();
+        self.0.state
+    }
+}
+
+impl From<Sha1> for ZeroizingSha1 {
+    fn from(sha1: Sha1) -> Self {
+        ZeroizingSha1(sha1)
+    }
+}
+
+impl Deref for ZeroizingSha1 {
+    type Target = Sha1;
+
+    fn deref(&self) -> &Sha1 {
+        &self.0
+    }
+}
+
+impl DerefMut for ZeroizingSha1 {
+    fn deref_mut(&mut self) -> &mut Sha1 {
+        &mut self.0
+    }
+}
+
+impl Drop for ZeroizingSha1 {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for ZeroizingSha1 {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, Sha1, ZeroizingSha1};
+    use core::mem::ManuallyDrop;
+    use zeroize::Zeroize;
+
+    #[test]
+    fn test_zeroize_on_drop() {
+        let mut sha1 = ManuallyDrop::new(ZeroizingSha1::new());
+        sha1.update(b"secret key material");
+
+        // The fields are plain data, so reading them after the destructor
+        // has run only observes what it left behind.
+        unsafe { ManuallyDrop::drop(&mut sha1) };
+        assert_eq!(sha1.0.state, [0; 5]);
+        assert_eq!(sha1.0.block, [0; 64]);
+        assert_eq!((sha1.0.block_len, sha1.0.length), (0, 0));
+    }
+
+    #[test]
+    fn test_same_digest() {
+        let mut plain = Sha1::new();
+        plain.update(b"abc");
+
+        let wrapped = ZeroizingSha1::from(plain);
+        assert_eq!(wrapped.finalize(), hash(b"abc"));
+    }
+
+    #[test]
+    fn test_finalize_variants() {
+        let mut sha1 = ZeroizingSha1::new();
+        sha1.update(b"abc");
+
+        let mut out = [0u8; 20];
+        sha1.clone().finalize_into(&mut out);
+        assert_eq!(out, hash(b"abc"));
+        assert_eq!(sha1.clone().finalize_with_len(), (hash(b"abc"), 3));
+        assert_eq!(
+            sha1.clone().finalize_words(),
+            Sha1::clone(&sha1).finalize_words()
+        );
+    }
+
+    #[test]
+    fn test_zeroize() {
+        let mut sha1 = Sha1::new();
+        sha1.update(b"secret key material");
+
+        sha1.zeroize();
+        assert_eq!(sha1.state, [0; 5]);
+        assert_eq!(sha1.block, [0; 64]);
+        assert_eq!((sha1.block_len, sha1.length), (0, 0));
+    }
+}